@@ -0,0 +1,40 @@
+//! Compares the candidate operations the solver does most (copying the full set, removing values, counting what's left
+//! and intersecting with a neighbour) between a `HashSet<u8>` and `Candidates`. Run it with `cargo bench`.
+
+use std::collections::HashSet;
+use std::hint::black_box;
+use std::time::Instant;
+
+use doku::Candidates;
+
+const ROUNDS: u32 = 200_000;
+
+fn main() {
+    let size: u8 = 16;
+
+    let start = Instant::now();
+    let mut total = 0;
+    let full: HashSet<u8> = (1..=size).collect();
+    for round in 0..ROUNDS {
+        let mut set = black_box(&full).clone();
+        set.remove(&((round % size as u32) as u8 + 1));
+        let other: HashSet<u8> = set.iter().filter(|v| *v % 2 == 0).cloned().collect();
+        total += set.intersection(&other).count() + set.len();
+    }
+    let hash_set_time = start.elapsed();
+
+    let start = Instant::now();
+    let mut bit_total = 0;
+    let full = Candidates::all(size);
+    for round in 0..ROUNDS {
+        let mut set = black_box(full);
+        set.remove((round % size as u32) as u8 + 1);
+        let other: Candidates = set.iter().filter(|v| *v % 2 == 0).collect();
+        bit_total += set.intersection(&other).len() + set.len();
+    }
+    let candidates_time = start.elapsed();
+
+    println!(
+        "HashSet<u8>: {hash_set_time:?} ({total}), Candidates: {candidates_time:?} ({bit_total})"
+    );
+}
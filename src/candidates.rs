@@ -0,0 +1,190 @@
+/// A set of values between 1 and 32, stored as a bit mask where bit `v - 1` is set if `v` is in the set. Grids never
+/// have more than 32 possible values, and a mask is much cheaper to copy, combine and compare than a `HashSet<u8>`.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Candidates(u32);
+
+impl Candidates {
+    /// The largest value a set of candidates can hold
+    pub const MAX_VALUE: u8 = 32;
+
+    pub fn new() -> Self {
+        Self(0)
+    }
+
+    /// All of the values from 1 up to and including `size`
+    pub fn all(size: u8) -> Self {
+        assert!(size <= Self::MAX_VALUE, "Candidates only go up to 32");
+        if size == Self::MAX_VALUE {
+            Self(u32::MAX)
+        } else {
+            Self((1 << size) - 1)
+        }
+    }
+
+    /// The bit for a value. Values outside of 1 to 32 can't be candidates, so asking about them is a bug.
+    fn bit(value: u8) -> u32 {
+        assert!(
+            (1..=Self::MAX_VALUE).contains(&value),
+            "Candidates only go from 1 to 32, got {value}"
+        );
+        1 << (value - 1)
+    }
+
+    pub fn contains(&self, value: u8) -> bool {
+        self.0 & Self::bit(value) != 0
+    }
+
+    /// Adds a value, returning whether it wasn't in the set yet
+    pub fn insert(&mut self, value: u8) -> bool {
+        let added = !self.contains(value);
+        self.0 |= Self::bit(value);
+        added
+    }
+
+    /// Removes a value, returning whether it was in the set
+    pub fn remove(&mut self, value: u8) -> bool {
+        let removed = self.contains(value);
+        self.0 &= !Self::bit(value);
+        removed
+    }
+
+    pub fn clear(&mut self) {
+        self.0 = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn union(&self, other: &Candidates) -> Candidates {
+        Self(self.0 | other.0)
+    }
+
+    pub fn intersection(&self, other: &Candidates) -> Candidates {
+        Self(self.0 & other.0)
+    }
+
+    pub fn difference(&self, other: &Candidates) -> Candidates {
+        Self(self.0 & !other.0)
+    }
+
+    pub fn min(&self) -> Option<u8> {
+        self.iter().next()
+    }
+
+    pub fn max(&self) -> Option<u8> {
+        match self.0 {
+            0 => None,
+            mask => Some((u32::BITS - mask.leading_zeros()) as u8),
+        }
+    }
+
+    /// The values in the set, from low to high
+    pub fn iter(&self) -> CandidatesIter {
+        CandidatesIter(self.0)
+    }
+}
+
+impl Extend<u8> for Candidates {
+    fn extend<T: IntoIterator<Item = u8>>(&mut self, values: T) {
+        for value in values {
+            self.insert(value);
+        }
+    }
+}
+
+impl FromIterator<u8> for Candidates {
+    fn from_iter<T: IntoIterator<Item = u8>>(values: T) -> Self {
+        let mut candidates = Candidates::new();
+        candidates.extend(values);
+        candidates
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Candidates {
+    fn from(values: [u8; N]) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+impl IntoIterator for Candidates {
+    type Item = u8;
+    type IntoIter = CandidatesIter;
+
+    fn into_iter(self) -> CandidatesIter {
+        self.iter()
+    }
+}
+
+impl std::fmt::Debug for Candidates {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Iterates over the values in a set of candidates from low to high, by repeatedly taking off the lowest set bit.
+pub struct CandidatesIter(u32);
+
+impl Iterator for CandidatesIter {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.0 == 0 {
+            return None;
+        }
+        let value = self.0.trailing_zeros() as u8 + 1;
+        self.0 &= self.0 - 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for CandidatesIter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_operations() {
+        let a = Candidates::from([1, 3, 5, 9]);
+        let b = Candidates::from([3, 4, 5]);
+        assert_eq!(a.len(), 4);
+        assert_eq!(a.union(&b), Candidates::from([1, 3, 4, 5, 9]));
+        assert_eq!(a.intersection(&b), Candidates::from([3, 5]));
+        assert_eq!(a.difference(&b), Candidates::from([1, 9]));
+        assert_eq!(a.iter().collect::<Vec<u8>>(), vec![1, 3, 5, 9]);
+        assert_eq!((a.min(), a.max()), (Some(1), Some(9)));
+        assert_eq!(Candidates::new().max(), None);
+    }
+
+    #[test]
+    fn insert_and_remove_report_changes() {
+        let mut candidates = Candidates::all(32);
+        assert_eq!(candidates.len(), 32);
+        assert!(candidates.remove(32));
+        assert!(!candidates.remove(32));
+        assert!(!candidates.contains(32));
+        assert!(candidates.insert(32));
+        assert!(!candidates.insert(32));
+        assert_eq!(candidates.max(), Some(32));
+    }
+
+    #[test]
+    fn values_outside_of_the_range_are_rejected() {
+        let candidates = Candidates::all(9);
+        for value in [0, 33] {
+            assert!(std::panic::catch_unwind(|| candidates.contains(value)).is_err());
+            assert!(std::panic::catch_unwind(|| { candidates }.insert(value)).is_err());
+            assert!(std::panic::catch_unwind(|| { candidates }.remove(value)).is_err());
+        }
+    }
+}
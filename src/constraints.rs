@@ -0,0 +1,208 @@
+pub mod anti_chess;
+pub mod arrow;
+pub mod cage;
+pub mod clone;
+pub mod line;
+pub mod line_clue;
+pub mod little_killer;
+pub mod outside;
+pub mod pair;
+pub mod quadruple;
+pub mod region;
+pub mod restricted_cell;
+pub mod sandwich;
+pub mod thermometer;
+
+use core::fmt::Debug;
+
+use crate::candidates::Candidates;
+use crate::data::{Coord, Grid, Reduction, Solution};
+
+/// A constraint is a rule that the values in a solved grid have to obey. The classic "every region holds each value
+/// exactly once" rule is one of them, variant rules such as killer cages are others. The grid consults its constraints
+/// to set up the initial candidates, to update candidates after a value has been placed, and to validate the solution.
+pub trait Constraint: Debug {
+    /// Whether the constraint involves the given cell, so that changes to it may let the constraint rule out more
+    /// candidates. Only used by the default `propagate` and `propagate_reductions`.
+    fn touches(&self, _coord: &Coord) -> bool {
+        false
+    }
+
+    /// Candidates that can be ruled out based on the current state of the grid. Constraints that look at all of their
+    /// cells at once implement this together with `touches`, and get the other reduction hooks for free.
+    fn reductions(&self, _grid: &Grid) -> Vec<Reduction> {
+        Vec::new()
+    }
+
+    /// Candidates that can be ruled out before we start solving, e.g. because of the given values.
+    fn initial_reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        self.reductions(grid)
+    }
+
+    /// Candidates that can be ruled out now that the solution's value has been placed in the grid.
+    fn propagate(&self, grid: &Grid, solution: &Solution) -> Vec<Reduction> {
+        if !self.touches(&solution.coord()) {
+            return Vec::new();
+        }
+        self.reductions(grid)
+    }
+
+    /// Candidates that can be ruled out now that the given reductions have been applied to the grid.
+    fn propagate_reductions(&self, grid: &Grid, reductions: &[Reduction]) -> Vec<Reduction> {
+        if !reductions.iter().any(|r| self.touches(&r.coord())) {
+            return Vec::new();
+        }
+        self.reductions(grid)
+    }
+
+    /// Checks that the values in a completely filled in grid obey the constraint.
+    fn validate(&self, grid: &Grid) -> Result<(), (&'static str, Coord)>;
+
+    /// Whether the constraint keeps the two (different) cells from holding the same value.
+    fn sees(&self, _a: &Coord, _b: &Coord) -> bool {
+        false
+    }
+}
+
+/// Reductions that remove `value` as a candidate from any of the given cells that still have it.
+pub fn remove_candidate<'a>(
+    grid: &Grid,
+    coords: impl IntoIterator<Item = &'a Coord>,
+    value: u8,
+) -> Vec<Reduction> {
+    coords
+        .into_iter()
+        .map(|coord| grid.cell(coord))
+        .filter(|cell| cell.is_empty() && cell.candidates.contains(value))
+        .map(|cell| Reduction::new(cell.coord, value))
+        .collect()
+}
+
+/// Removes the candidates of cell `a` that don't have a value in cell `b` that `allowed` accepts.
+pub fn support_reductions(
+    grid: &Grid,
+    a: &Coord,
+    b: &Coord,
+    allowed: impl Fn(u8, u8) -> bool,
+) -> Vec<Reduction> {
+    let a_cell = grid.cell(a);
+    if !a_cell.is_empty() {
+        return Vec::new();
+    }
+    let b_values = grid.cell(b).possible_values();
+    let sees = grid.sees(a, b);
+    a_cell
+        .candidates
+        .iter()
+        .filter(|&candidate| {
+            !b_values
+                .iter()
+                .any(|value| allowed(candidate, value) && !(sees && candidate == value))
+        })
+        .map(|candidate| Reduction::new(*a, candidate))
+        .collect()
+}
+
+/// Reductions that remove every candidate of the given cells that isn't among its `possible` values.
+pub fn restrict_candidates(
+    grid: &Grid,
+    coords: &[Coord],
+    possible: &[Candidates],
+) -> Vec<Reduction> {
+    let mut reductions: Vec<Reduction> = Vec::new();
+    for (coord, values) in coords.iter().zip(possible.iter()) {
+        let cell = grid.cell(coord);
+        if !cell.is_empty() {
+            continue;
+        }
+        for candidate in cell.candidates.difference(values) {
+            reductions.push(Reduction::new(*coord, candidate));
+        }
+    }
+    reductions
+}
+
+/// How a search over the values of a group of cells goes on from the values assigned to its first cells so far.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Filling {
+    /// Try the values of the next cell
+    Incomplete,
+    /// However the remaining cells get filled in, the rule can't hold
+    Impossible,
+    /// The rule holds, whatever the remaining cells hold
+    Valid,
+}
+
+/// Tries every way to fill in the given cells in order, each with one of its `options`, where cells that see each other
+/// never hold the same value. `check` gets the values of the first cells every time another one has been assigned and
+/// decides how to go on; a complete filling that's still `Incomplete` doesn't count. The result has the values each cell
+/// holds in at least one valid filling. Cells after the ones a valid filling assigned can hold any of their options that
+/// don't clash with the assigned values.
+pub fn search_fillings(
+    grid: &Grid,
+    coords: &[Coord],
+    options: &[Candidates],
+    check: impl FnMut(&[u8]) -> Filling,
+) -> Vec<Candidates> {
+    let mut search = FillingSearch {
+        // Worked out up front because the search checks this very often
+        sees: coords
+            .iter()
+            .map(|a| coords.iter().map(|b| grid.sees(a, b)).collect())
+            .collect(),
+        options,
+        check,
+        assigned: Vec::with_capacity(coords.len()),
+        possible: vec![Candidates::new(); coords.len()],
+    };
+    search.assign();
+    search.possible
+}
+
+struct FillingSearch<'a, F> {
+    sees: Vec<Vec<bool>>,
+    options: &'a [Candidates],
+    check: F,
+    assigned: Vec<u8>,
+    possible: Vec<Candidates>,
+}
+
+impl<F: FnMut(&[u8]) -> Filling> FillingSearch<'_, F> {
+    fn assign(&mut self) {
+        let index = self.assigned.len();
+        match (self.check)(&self.assigned) {
+            Filling::Impossible => return,
+            Filling::Incomplete if index == self.options.len() => return,
+            Filling::Incomplete => {}
+            Filling::Valid => {
+                for (i, value) in self.assigned.iter().enumerate() {
+                    self.possible[i].insert(*value);
+                }
+                for i in index..self.options.len() {
+                    for value in self.options[i] {
+                        if !self.conflicts(i, value) {
+                            self.possible[i].insert(value);
+                        }
+                    }
+                }
+                return;
+            }
+        }
+        for value in self.options[index] {
+            if self.conflicts(index, value) {
+                continue;
+            }
+            self.assigned.push(value);
+            self.assign();
+            self.assigned.pop();
+        }
+    }
+
+    /// Whether the value of the cell at the given index clashes with one of the assigned values
+    fn conflicts(&self, index: usize, value: u8) -> bool {
+        self.assigned
+            .iter()
+            .enumerate()
+            .any(|(i, other)| *other == value && self.sees[i][index])
+    }
+}
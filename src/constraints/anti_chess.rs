@@ -0,0 +1,115 @@
+use super::{remove_candidate, Constraint};
+use crate::data::{Coord, Grid, Reduction, Solution};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ChessMove {
+    Knight,
+    King,
+}
+
+impl ChessMove {
+    fn offsets(&self) -> &'static [(i16, i16)] {
+        match self {
+            ChessMove::Knight => &[
+                (1, 2),
+                (2, 1),
+                (2, -1),
+                (1, -2),
+                (-1, -2),
+                (-2, -1),
+                (-2, 1),
+                (-1, 2),
+            ],
+            ChessMove::King => &[
+                (0, 1),
+                (1, 1),
+                (1, 0),
+                (1, -1),
+                (0, -1),
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+            ],
+        }
+    }
+}
+
+/// Cells that are a chess knight's (or king's) move apart can't hold the same value. Unlike a region this is a rule
+/// between pairs of cells, and it applies to the whole grid.
+#[derive(Debug)]
+pub struct AntiChess {
+    chess_move: ChessMove,
+}
+
+impl AntiChess {
+    pub fn new(chess_move: ChessMove) -> Self {
+        Self { chess_move }
+    }
+
+    /// The cells that are a single move away from the given coordinate
+    fn neighbours(&self, grid: &Grid, coord: &Coord) -> Vec<Coord> {
+        self.chess_move
+            .offsets()
+            .iter()
+            .filter_map(|(dx, dy)| grid.coord_at(coord.x() as i16 + dx, coord.y() as i16 + dy))
+            .collect()
+    }
+}
+
+impl Constraint for AntiChess {
+    fn initial_reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        grid.cells()
+            .iter()
+            .filter_map(|cell| cell.value.map(|v| (cell.coord, v)))
+            .flat_map(|(coord, value)| {
+                remove_candidate(grid, self.neighbours(grid, &coord).iter(), value)
+            })
+            .collect()
+    }
+
+    fn propagate(&self, grid: &Grid, solution: &Solution) -> Vec<Reduction> {
+        remove_candidate(
+            grid,
+            self.neighbours(grid, &solution.coord()).iter(),
+            solution.value(),
+        )
+    }
+
+    fn validate(&self, grid: &Grid) -> Result<(), (&'static str, Coord)> {
+        for cell in grid.cells().iter() {
+            if self
+                .neighbours(grid, &cell.coord)
+                .iter()
+                .any(|coord| grid.cell(coord).value == cell.value)
+            {
+                return Err(("A value occurs twice a chess move apart", cell.coord));
+            }
+        }
+        Ok(())
+    }
+
+    fn sees(&self, a: &Coord, b: &Coord) -> bool {
+        let dx = a.x() as i16 - b.x() as i16;
+        let dy = a.y() as i16 - b.y() as i16;
+        self.chess_move.offsets().contains(&(dx, dy))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn knight_sees_cells_a_knights_move_away() {
+        let anti_knight = AntiChess::new(ChessMove::Knight);
+        assert!(anti_knight.sees(&Coord::new(4, 4), &Coord::new(5, 6)));
+        assert!(anti_knight.sees(&Coord::new(4, 4), &Coord::new(2, 3)));
+        assert!(!anti_knight.sees(&Coord::new(4, 4), &Coord::new(5, 5)));
+
+        let mut grid = Grid::new(9).unwrap();
+        grid.add_constraint(anti_knight);
+        assert!(grid.sees(&Coord::new(0, 0), &Coord::new(1, 2)));
+        assert!(!grid.sees(&Coord::new(0, 0), &Coord::new(3, 3)));
+        assert_eq!(grid.constraints()[0].initial_reductions(&grid).len(), 0);
+    }
+}
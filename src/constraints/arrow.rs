@@ -0,0 +1,186 @@
+use super::cage::sum_candidates;
+use super::{restrict_candidates, Constraint};
+use crate::candidates::Candidates;
+use crate::data::{Coord, Grid, Reduction};
+use crate::error::DokuError;
+
+/// The values along an arrow add up to the number in its circle. The circle is usually a single cell, but it can also be
+/// a "pill" of two cells that are read as a two digit number. Values on the arrow may repeat unless the cells see each
+/// other.
+#[derive(Debug)]
+pub struct Arrow {
+    circle: Vec<Coord>,
+    arrow: Vec<Coord>,
+}
+
+impl Arrow {
+    pub fn new(circle: Vec<Coord>, arrow: Vec<Coord>) -> Result<Self, DokuError> {
+        if !(1..=2).contains(&circle.len()) {
+            return Err(DokuError::InvalidRule(
+                "The circle of an arrow must be one cell or a pill of two cells",
+            ));
+        }
+        Ok(Self { circle, arrow })
+    }
+
+    fn circle_number(digits: &[u8]) -> u32 {
+        digits
+            .iter()
+            .fold(0, |number, digit| number * 10 + *digit as u32)
+    }
+
+    /// All the numbers the circle can still read, along with the digits that make them up
+    fn circle_options(&self, grid: &Grid) -> Vec<Vec<u8>> {
+        let mut options: Vec<Vec<u8>> = vec![Vec::new()];
+        for (i, coord) in self.circle.iter().enumerate() {
+            let values = grid.cell(coord).possible_values();
+            options = options
+                .into_iter()
+                .flat_map(|digits| {
+                    values
+                        .iter()
+                        .filter(|&v| {
+                            !digits.iter().enumerate().any(|(j, d)| {
+                                *d == v && grid.sees(&self.circle[j], &self.circle[i])
+                            })
+                        })
+                        .map(|v| {
+                            let mut next = digits.clone();
+                            next.push(v);
+                            next
+                        })
+                        .collect::<Vec<Vec<u8>>>()
+                })
+                .collect();
+        }
+        options
+    }
+
+    /// Narrows the circle down to the numbers the arrow can add up to, and each cell on the arrow down to the values that
+    /// allow the arrow to add up to one of the numbers in the circle.
+    fn sum_reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        let mut circle_possible: Vec<Candidates> = vec![Candidates::new(); self.circle.len()];
+        let mut arrow_possible: Vec<Candidates> = vec![Candidates::new(); self.arrow.len()];
+        for digits in self.circle_options(grid) {
+            let possible = sum_candidates(grid, &self.arrow, Self::circle_number(&digits));
+            if possible.iter().any(|values| values.is_empty()) {
+                continue;
+            }
+            for (i, digit) in digits.iter().enumerate() {
+                circle_possible[i].insert(*digit);
+            }
+            for (i, values) in possible.into_iter().enumerate() {
+                arrow_possible[i].extend(values);
+            }
+        }
+
+        let coords: Vec<Coord> = self
+            .circle
+            .iter()
+            .chain(self.arrow.iter())
+            .cloned()
+            .collect();
+        circle_possible.extend(arrow_possible);
+        restrict_candidates(grid, &coords, &circle_possible)
+    }
+}
+
+impl Constraint for Arrow {
+    fn touches(&self, coord: &Coord) -> bool {
+        self.circle.contains(coord) || self.arrow.contains(coord)
+    }
+
+    fn reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        self.sum_reductions(grid)
+    }
+
+    fn validate(&self, grid: &Grid) -> Result<(), (&'static str, Coord)> {
+        let circle: Vec<u8> = self
+            .circle
+            .iter()
+            .filter_map(|coord| grid.cell(coord).value)
+            .collect();
+        let sum: u32 = self
+            .arrow
+            .iter()
+            .filter_map(|coord| grid.cell(coord).value)
+            .map(|v| v as u32)
+            .sum();
+        if sum != Self::circle_number(&circle) {
+            return Err((
+                "The values on an arrow do not add up to its circle",
+                self.circle[0],
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arrow_of_two_cells_rules_out_small_circles() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.add_constraint(
+            Arrow::new(
+                vec![Coord::new(0, 0)],
+                vec![Coord::new(1, 0), Coord::new(2, 0)],
+            )
+            .unwrap(),
+        );
+        grid.compute_candidates();
+        // The arrow cells are in the same row, so they add up to at least 1 + 2
+        assert_eq!(
+            grid.cell(&Coord::new(0, 0)).candidates,
+            Candidates::from_iter(3..=9)
+        );
+        assert_eq!(
+            grid.cell(&Coord::new(1, 0)).candidates,
+            Candidates::from_iter(1..=8)
+        );
+
+        grid.apply_reductions(&[Reduction::new(Coord::new(0, 0), 9)]);
+        assert_eq!(
+            grid.cell(&Coord::new(1, 0)).candidates,
+            Candidates::from_iter(1..=7)
+        );
+    }
+
+    #[test]
+    fn pill_reads_as_a_two_digit_number() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.add_constraint(
+            Arrow::new(
+                vec![Coord::new(0, 0), Coord::new(1, 0)],
+                vec![Coord::new(0, 1), Coord::new(0, 2)],
+            )
+            .unwrap(),
+        );
+        grid.compute_candidates();
+        // The arrow adds up to at most 8 + 9, so the pill reads 12 up to 17
+        assert_eq!(
+            grid.cell(&Coord::new(0, 0)).candidates,
+            Candidates::from([1])
+        );
+        assert_eq!(
+            grid.cell(&Coord::new(1, 0)).candidates,
+            Candidates::from_iter(2..=7)
+        );
+    }
+
+    #[test]
+    fn circles_hold_one_or_two_cells() {
+        let arrow = vec![Coord::new(0, 1)];
+        assert!(matches!(
+            Arrow::new(Vec::new(), arrow.clone()),
+            Err(DokuError::InvalidRule(_))
+        ));
+        let long_circle: Vec<Coord> = (0..3).map(|x| Coord::new(x, 0)).collect();
+        assert!(matches!(
+            Arrow::new(long_circle, arrow),
+            Err(DokuError::InvalidRule(_))
+        ));
+    }
+}
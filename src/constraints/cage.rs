@@ -0,0 +1,271 @@
+use std::collections::BTreeSet;
+
+use super::{remove_candidate, search_fillings, Constraint, Filling};
+use crate::candidates::Candidates;
+use crate::data::{Coord, Grid, Reduction, Region, Solution};
+use crate::error::DokuError;
+
+/// The arithmetic operation that combines the values in a cage into its target. Killer cages always add up their
+/// values, while KenKen (or Calcudoku) cages can use any of these.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CageOperation {
+    Sum,
+    /// The difference between the two values of a cage of two cells
+    Difference,
+    Product,
+    /// The bigger of the two values of a cage of two cells divided by the smaller one
+    Quotient,
+}
+
+impl CageOperation {
+    /// The result of the operation on the given values, if it is defined for them. Products too big for a `u32` can't
+    /// make any target, so they count as undefined.
+    pub fn apply(&self, values: &[u8]) -> Option<u32> {
+        match self {
+            CageOperation::Sum => Some(values.iter().map(|v| *v as u32).sum()),
+            CageOperation::Product => values
+                .iter()
+                .try_fold(1u32, |product, v| product.checked_mul(*v as u32)),
+            CageOperation::Difference => match values {
+                [a, b] => Some(a.abs_diff(*b) as u32),
+                _ => None,
+            },
+            CageOperation::Quotient => match values {
+                [a, b] if *a.min(b) > 0 && a.max(b) % a.min(b) == 0 => {
+                    Some((a.max(b) / a.min(b)) as u32)
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for CageOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            CageOperation::Sum => '+',
+            CageOperation::Difference => '−',
+            CageOperation::Product => '×',
+            CageOperation::Quotient => '÷',
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+/// A cage is a group of cells whose values combine into a given target. In Killer Sudoku the values add up to the target
+/// without repeating a digit. In KenKen the cage can use any `CageOperation`, and digits may repeat as long as the rows
+/// and columns allow it. Unlike a `Region`, a cage does not need to contain every digit.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Cage {
+    operation: CageOperation,
+    target: u32,
+    /// Whether the values in the cage have to be different from each other
+    distinct: bool,
+    coords: BTreeSet<Coord>,
+}
+
+impl Cage {
+    /// Creates a killer cage, whose values are all different and add up to `sum`
+    pub fn new(sum: u32, coords: BTreeSet<Coord>) -> Self {
+        Self {
+            operation: CageOperation::Sum,
+            target: sum,
+            distinct: true,
+            coords,
+        }
+    }
+
+    /// Creates a KenKen cage, whose values give `target` when combined with `operation`. Differences and quotients are
+    /// only defined for cages of two cells.
+    pub fn arithmetic(
+        operation: CageOperation,
+        target: u32,
+        coords: BTreeSet<Coord>,
+    ) -> Result<Self, DokuError> {
+        let two_cells_only = matches!(
+            operation,
+            CageOperation::Difference | CageOperation::Quotient
+        );
+        if two_cells_only && coords.len() != 2 {
+            return Err(DokuError::InvalidRule(
+                "Difference and quotient cages must have exactly two cells",
+            ));
+        }
+        Ok(Self {
+            operation,
+            target,
+            distinct: false,
+            coords,
+        })
+    }
+
+    /// The sum of the cage's values, if the cage is a sum cage
+    pub fn sum(&self) -> Option<u32> {
+        match self.operation {
+            CageOperation::Sum => Some(self.target),
+            _ => None,
+        }
+    }
+
+    pub fn operation(&self) -> CageOperation {
+        self.operation
+    }
+
+    pub fn target(&self) -> u32 {
+        self.target
+    }
+
+    pub fn contains_coord(&self, coord: &Coord) -> bool {
+        self.coords.contains(coord)
+    }
+
+    pub fn cell_coords(&self) -> &BTreeSet<Coord> {
+        &self.coords
+    }
+
+    pub fn is_within(&self, region: &Region) -> bool {
+        region.contains_coords(&self.coords)
+    }
+}
+
+impl std::fmt::Display for Cage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.operation {
+            CageOperation::Sum => write!(
+                f,
+                "Cage of {} cells summing to {}",
+                self.coords.len(),
+                self.target
+            ),
+            operation => write!(
+                f,
+                "Cage of {} cells making {}{}",
+                self.coords.len(),
+                self.target,
+                operation
+            ),
+        }
+    }
+}
+
+impl Constraint for Cage {
+    fn initial_reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        if !self.distinct {
+            return Vec::new();
+        }
+        self.coords
+            .iter()
+            .filter_map(|coord| grid.cell(coord).value)
+            .flat_map(|value| remove_candidate(grid, self.coords.iter(), value))
+            .collect()
+    }
+
+    fn propagate(&self, grid: &Grid, solution: &Solution) -> Vec<Reduction> {
+        if !self.distinct || !self.contains_coord(&solution.coord()) {
+            return Vec::new();
+        }
+        remove_candidate(grid, self.coords.iter(), solution.value())
+    }
+
+    fn sees(&self, a: &Coord, b: &Coord) -> bool {
+        self.distinct && self.contains_coord(a) && self.contains_coord(b)
+    }
+
+    fn validate(&self, grid: &Grid) -> Result<(), (&'static str, Coord)> {
+        let mut found_values = Candidates::new();
+        let mut values: Vec<u8> = Vec::with_capacity(self.coords.len());
+        for coord in self.coords.iter() {
+            let cell = grid.cell(coord);
+            let Some(value) = cell.value else {
+                return Err(("A cell has no value.", cell.coord));
+            };
+            if !found_values.insert(value) && self.distinct {
+                return Err(("A value occurs twice in a cage", cell.coord));
+            }
+            values.push(value);
+        }
+        if self.operation.apply(&values) != Some(self.target) {
+            let coord = *self.coords.iter().next().unwrap();
+            return Err(("The values in a cage do not make its target", coord));
+        }
+        Ok(())
+    }
+}
+
+/// Works out which values each of the given cells can still take if their values have to add up to `sum`, taking into
+/// account that cells which see each other can't hold the same value. The result has the possible values for each of
+/// the `coords`, in the same order.
+pub fn sum_candidates(grid: &Grid, coords: &[Coord], sum: u32) -> Vec<Candidates> {
+    let options: Vec<Candidates> = coords
+        .iter()
+        .map(|coord| grid.cell(coord).possible_values())
+        .collect();
+    sum_options(grid, coords, &options, sum)
+}
+
+/// Works out which values each of the given cells can still take if combining their values with `operation` has to give
+/// `target`, taking into account that cells which see each other can't hold the same value. The result has the possible
+/// values for each of the `coords`, in the same order.
+pub fn operation_candidates(
+    grid: &Grid,
+    coords: &[Coord],
+    operation: CageOperation,
+    target: u32,
+) -> Vec<Candidates> {
+    if operation == CageOperation::Sum {
+        return sum_candidates(grid, coords, target);
+    }
+    let options: Vec<Candidates> = coords
+        .iter()
+        .map(|coord| grid.cell(coord).possible_values())
+        .collect();
+    search_fillings(grid, coords, &options, |assigned| {
+        if assigned.len() == coords.len() {
+            return if operation.apply(assigned) == Some(target) {
+                Filling::Valid
+            } else {
+                Filling::Impossible
+            };
+        }
+        // Every partial product has to divide the target, which cuts the search short for most values
+        if operation == CageOperation::Product {
+            match operation.apply(assigned) {
+                Some(product) if product != 0 && target.is_multiple_of(product) => {}
+                _ => return Filling::Impossible,
+            }
+        }
+        Filling::Incomplete
+    })
+}
+
+/// Like `sum_candidates`, but with the values each cell can take given by `options` rather than by the grid.
+pub fn sum_options(
+    grid: &Grid,
+    coords: &[Coord],
+    options: &[Candidates],
+    sum: u32,
+) -> Vec<Candidates> {
+    // The smallest and largest sums the cells from a given index onwards can still make, so we can stop early when the
+    // remaining sum is out of reach
+    let mut min_rest: Vec<u32> = vec![0; coords.len() + 1];
+    let mut max_rest: Vec<u32> = vec![0; coords.len() + 1];
+    for i in (0..coords.len()).rev() {
+        min_rest[i] = min_rest[i + 1] + options[i].min().unwrap_or(0) as u32;
+        max_rest[i] = max_rest[i + 1] + options[i].max().unwrap_or(0) as u32;
+    }
+
+    search_fillings(grid, coords, options, |assigned| {
+        let total: u32 = assigned.iter().map(|v| *v as u32).sum();
+        let Some(remaining) = sum.checked_sub(total) else {
+            return Filling::Impossible;
+        };
+        let index = assigned.len();
+        if remaining < min_rest[index] || remaining > max_rest[index] {
+            Filling::Impossible
+        } else if index == coords.len() {
+            Filling::Valid
+        } else {
+            Filling::Incomplete
+        }
+    })
+}
@@ -0,0 +1,111 @@
+use super::{support_reductions, Constraint};
+use crate::data::{Coord, Grid, Reduction, Solution};
+use crate::error::DokuError;
+
+/// Two shapes of the same size and form that contain the same digits in the same relative positions. Each cell of one
+/// shape has a twin in the other that holds the same value, so any candidate removed from a cell is removed from its twin
+/// as well.
+#[derive(Debug)]
+pub struct CloneRegions {
+    twins: Vec<(Coord, Coord)>,
+}
+
+impl CloneRegions {
+    /// Creates clones from two lists of cells, where the cells at the same index in both lists are each other's twin.
+    /// The clone has to be a translated copy of the original, so every cell is moved by the same offset.
+    pub fn new(original: Vec<Coord>, clone: Vec<Coord>) -> Result<Self, DokuError> {
+        if original.len() != clone.len() {
+            return Err(DokuError::InvalidRule(
+                "Clone regions must have the same number of cells",
+            ));
+        }
+        let twins: Vec<(Coord, Coord)> = original.into_iter().zip(clone).collect();
+        let offset =
+            |(a, b): &(Coord, Coord)| (b.x() as i16 - a.x() as i16, b.y() as i16 - a.y() as i16);
+        if twins.iter().any(|twin| offset(twin) != offset(&twins[0])) {
+            return Err(DokuError::InvalidRule(
+                "A clone must be the original region moved by the same amount for every cell",
+            ));
+        }
+        Ok(Self { twins })
+    }
+
+    /// Mirrors the candidates of the twins of the given cells
+    fn twin_reductions(&self, grid: &Grid, coords: &[Coord]) -> Vec<Reduction> {
+        let mut reductions: Vec<Reduction> = Vec::new();
+        for (a, b) in self.twins.iter() {
+            if coords.contains(a) || coords.contains(b) {
+                reductions.extend(support_reductions(grid, a, b, |a, b| a == b));
+                reductions.extend(support_reductions(grid, b, a, |b, a| a == b));
+            }
+        }
+        reductions
+    }
+}
+
+impl Constraint for CloneRegions {
+    fn initial_reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        let coords: Vec<Coord> = self.twins.iter().map(|(a, _)| *a).collect();
+        self.twin_reductions(grid, &coords)
+    }
+
+    fn propagate(&self, grid: &Grid, solution: &Solution) -> Vec<Reduction> {
+        self.twin_reductions(grid, &[solution.coord()])
+    }
+
+    fn propagate_reductions(&self, grid: &Grid, reductions: &[Reduction]) -> Vec<Reduction> {
+        let coords: Vec<Coord> = reductions.iter().map(|r| r.coord()).collect();
+        self.twin_reductions(grid, &coords)
+    }
+
+    fn validate(&self, grid: &Grid) -> Result<(), (&'static str, Coord)> {
+        for (a, b) in self.twins.iter() {
+            if grid.cell(a).value != grid.cell(b).value {
+                return Err(("A cell holds a different value than its clone", *b));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::candidates::Candidates;
+
+    #[test]
+    fn reductions_are_mirrored_onto_twins() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.set_given_value(Coord::new(0, 0), 3);
+        grid.add_constraint(
+            CloneRegions::new(
+                vec![Coord::new(0, 0), Coord::new(1, 0)],
+                vec![Coord::new(6, 6), Coord::new(7, 6)],
+            )
+            .unwrap(),
+        );
+        grid.compute_candidates();
+        assert_eq!(
+            grid.cell(&Coord::new(6, 6)).candidates,
+            Candidates::from([3])
+        );
+
+        grid.apply_reductions(&[Reduction::new(Coord::new(7, 6), 5)]);
+        assert!(!grid.cell(&Coord::new(1, 0)).candidates.contains(5));
+        assert!(grid.cell(&Coord::new(1, 0)).candidates.contains(6));
+    }
+
+    #[test]
+    fn clones_must_have_the_same_shape() {
+        let original = vec![Coord::new(0, 0), Coord::new(1, 0), Coord::new(1, 1)];
+        let moved = vec![Coord::new(4, 3), Coord::new(5, 3), Coord::new(5, 4)];
+        assert!(CloneRegions::new(original.clone(), moved).is_ok());
+        let mirrored = vec![Coord::new(5, 3), Coord::new(4, 3), Coord::new(4, 4)];
+        assert!(matches!(
+            CloneRegions::new(original.clone(), mirrored),
+            Err(DokuError::InvalidRule(_))
+        ));
+        let bent = vec![Coord::new(4, 3), Coord::new(5, 3), Coord::new(6, 4)];
+        assert!(CloneRegions::new(original, bent).is_err());
+    }
+}
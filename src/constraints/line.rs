@@ -0,0 +1,278 @@
+use std::collections::{BTreeSet, HashSet};
+
+use super::cage::sum_candidates;
+use super::{restrict_candidates, support_reductions, Constraint};
+use crate::candidates::Candidates;
+use crate::data::{Coord, Grid, Reduction};
+use crate::error::DokuError;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LineKind {
+    /// German whispers: neighbouring cells on the line differ by at least half the grid size, rounded up (5 on 9x9)
+    Whisper,
+    /// A set of consecutive values in any order, without repeats
+    Renban,
+    /// The line reads the same from either end
+    Palindrome,
+    /// The line is divided into segments by the boxes it passes through, and every segment has the same sum
+    RegionSum,
+}
+
+/// A line constraint on an ordered path of cells.
+#[derive(Debug)]
+pub struct Line {
+    kind: LineKind,
+    coords: Vec<Coord>,
+    /// The runs of consecutive cells on the line that lie in the same box, for region sum lines
+    segments: Vec<Vec<Coord>>,
+}
+
+impl Line {
+    pub fn new(grid: &Grid, kind: LineKind, coords: Vec<Coord>) -> Result<Self, DokuError> {
+        if coords.iter().collect::<BTreeSet<_>>().len() != coords.len() {
+            return Err(DokuError::InvalidRule(
+                "A line can't pass through the same cell twice",
+            ));
+        }
+        let mut segments: Vec<Vec<Coord>> = Vec::new();
+        if kind == LineKind::RegionSum {
+            let box_index = |coord: &Coord| {
+                grid.regions_for_coord(coord)
+                    .find(|&id| grid.region_by_id(id).is_square())
+            };
+            for coord in coords.iter() {
+                match segments.last_mut() {
+                    Some(segment) if box_index(&segment[0]) == box_index(coord) => {
+                        segment.push(*coord)
+                    }
+                    _ => segments.push(vec![*coord]),
+                }
+            }
+        }
+        Ok(Self {
+            kind,
+            coords,
+            segments,
+        })
+    }
+
+    fn whisper_difference(grid: &Grid) -> u8 {
+        grid.size().div_ceil(2)
+    }
+
+    fn line_reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        match self.kind {
+            LineKind::Whisper => {
+                let difference = Self::whisper_difference(grid);
+                self.neighbour_reductions(grid, |a, b| a.abs_diff(b) >= difference)
+            }
+            LineKind::Palindrome => {
+                let mut reductions: Vec<Reduction> = Vec::new();
+                for (a, b) in self.coords.iter().zip(self.coords.iter().rev()) {
+                    reductions.extend(support_reductions(grid, a, b, |a, b| a == b));
+                }
+                reductions
+            }
+            LineKind::Renban => self.renban_reductions(grid),
+            LineKind::RegionSum => self.region_sum_reductions(grid),
+        }
+    }
+
+    /// Removes the candidates that don't work with any of the values of a neighbouring cell on the line
+    fn neighbour_reductions(
+        &self,
+        grid: &Grid,
+        allowed: impl Fn(u8, u8) -> bool,
+    ) -> Vec<Reduction> {
+        let mut reductions: Vec<Reduction> = Vec::new();
+        for pair in self.coords.windows(2) {
+            reductions.extend(support_reductions(grid, &pair[0], &pair[1], &allowed));
+            reductions.extend(support_reductions(grid, &pair[1], &pair[0], &allowed));
+        }
+        reductions
+    }
+
+    /// A renban line holds one of the runs of consecutive values of the line's length. A run is still possible if every
+    /// cell can take one of its values and every one of its values can go in one of the cells.
+    fn renban_reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        let length = self.coords.len() as u8;
+        let values: Vec<Candidates> = self
+            .coords
+            .iter()
+            .map(|coord| grid.cell(coord).possible_values())
+            .collect();
+        let mut possible: Vec<Candidates> = vec![Candidates::new(); self.coords.len()];
+        for start in 1..=(grid.size() + 1).saturating_sub(length) {
+            let run: Candidates = (start..start + length).collect();
+            let fits_cells = values.iter().all(|v| !v.intersection(&run).is_empty());
+            let fits_values = run.iter().all(|r| values.iter().any(|v| v.contains(r)));
+            if !fits_cells || !fits_values {
+                continue;
+            }
+            for (cell_possible, cell_values) in possible.iter_mut().zip(values.iter()) {
+                *cell_possible = cell_possible.union(&cell_values.intersection(&run));
+            }
+        }
+        restrict_candidates(grid, &self.coords, &possible)
+    }
+
+    /// Every segment has to be able to make up the same sum. The candidates that don't help any segment make a sum that
+    /// all of the other segments can make too are removed.
+    fn region_sum_reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        let mut possible: Vec<Candidates> = vec![Candidates::new(); self.coords.len()];
+        let max_sum: u32 = grid.possible_values().map(|v| v as u32).sum();
+        for sum in 1..=max_sum {
+            let segments_possible: Vec<Vec<Candidates>> = self
+                .segments
+                .iter()
+                .map(|segment| sum_candidates(grid, segment, sum))
+                .collect();
+            if segments_possible
+                .iter()
+                .flatten()
+                .any(|values| values.is_empty())
+            {
+                continue;
+            }
+            for (cell_possible, values) in
+                possible.iter_mut().zip(segments_possible.iter().flatten())
+            {
+                *cell_possible = cell_possible.union(values);
+            }
+        }
+        let coords: Vec<Coord> = self.segments.iter().flatten().cloned().collect();
+        restrict_candidates(grid, &coords, &possible)
+    }
+}
+
+impl Constraint for Line {
+    fn touches(&self, coord: &Coord) -> bool {
+        self.coords.contains(coord)
+    }
+
+    fn reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        self.line_reductions(grid)
+    }
+
+    fn validate(&self, grid: &Grid) -> Result<(), (&'static str, Coord)> {
+        let values: Vec<u8> = self
+            .coords
+            .iter()
+            .map(|coord| grid.cell(coord).value.unwrap_or(0))
+            .collect();
+        let valid = match self.kind {
+            LineKind::Whisper => {
+                let difference = Self::whisper_difference(grid);
+                values
+                    .windows(2)
+                    .all(|pair| pair[0].abs_diff(pair[1]) >= difference)
+            }
+            LineKind::Renban => {
+                let distinct: HashSet<u8> = values.iter().cloned().collect();
+                let min = values.iter().min().unwrap();
+                let max = values.iter().max().unwrap();
+                distinct.len() == values.len() && (max - min) as usize == values.len() - 1
+            }
+            LineKind::Palindrome => values.iter().eq(values.iter().rev()),
+            LineKind::RegionSum => {
+                let sums: HashSet<u32> = self
+                    .segments
+                    .iter()
+                    .map(|segment| {
+                        segment
+                            .iter()
+                            .filter_map(|coord| grid.cell(coord).value)
+                            .map(|v| v as u32)
+                            .sum()
+                    })
+                    .collect();
+                sums.len() == 1
+            }
+        };
+        if !valid {
+            return Err((
+                "The values on a line do not follow its rule",
+                self.coords[0],
+            ));
+        }
+        Ok(())
+    }
+
+    fn sees(&self, a: &Coord, b: &Coord) -> bool {
+        match self.kind {
+            LineKind::Renban => self.touches(a) && self.touches(b),
+            LineKind::Whisper => self
+                .coords
+                .windows(2)
+                .any(|pair| (pair[0] == *a && pair[1] == *b) || (pair[0] == *b && pair[1] == *a)),
+            LineKind::Palindrome | LineKind::RegionSum => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row_coords(length: u8) -> Vec<Coord> {
+        (0..length).map(|x| Coord::new(x, 0)).collect()
+    }
+
+    #[test]
+    fn whisper_rules_out_five() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.add_constraint(Line::new(&grid, LineKind::Whisper, row_coords(2)).unwrap());
+        grid.compute_candidates();
+        assert!(!grid.cell(&Coord::new(0, 0)).candidates.contains(5));
+        assert_eq!(grid.cell(&Coord::new(1, 0)).candidates.len(), 8);
+    }
+
+    #[test]
+    fn renban_next_to_a_given_value() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.set_given_value(Coord::new(0, 0), 9);
+        grid.add_constraint(Line::new(&grid, LineKind::Renban, row_coords(3)).unwrap());
+        grid.compute_candidates();
+        assert_eq!(
+            grid.cell(&Coord::new(1, 0)).candidates,
+            Candidates::from([7, 8])
+        );
+    }
+
+    #[test]
+    fn palindrome_mirrors_candidates() {
+        let mut grid = Grid::new(9).unwrap();
+        let coords = vec![
+            Coord::new(0, 0),
+            Coord::new(1, 1),
+            Coord::new(2, 2),
+            Coord::new(3, 3),
+        ];
+        grid.set_given_value(Coord::new(0, 0), 4);
+        grid.add_constraint(Line::new(&grid, LineKind::Palindrome, coords).unwrap());
+        grid.compute_candidates();
+        assert_eq!(
+            grid.cell(&Coord::new(3, 3)).candidates,
+            Candidates::from([4])
+        );
+    }
+
+    #[test]
+    fn region_sum_line_is_split_by_boxes() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.set_given_value(Coord::new(3, 0), 3);
+        let line = Line::new(
+            &grid,
+            LineKind::RegionSum,
+            vec![Coord::new(1, 0), Coord::new(2, 0), Coord::new(3, 0)],
+        )
+        .unwrap();
+        assert_eq!(line.segments.len(), 2);
+        grid.add_constraint(line);
+        grid.compute_candidates();
+        assert_eq!(
+            grid.cell(&Coord::new(1, 0)).candidates,
+            Candidates::from([1, 2])
+        );
+    }
+}
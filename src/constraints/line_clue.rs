@@ -0,0 +1,193 @@
+use super::outside::OutsideCoord;
+use super::{restrict_candidates, search_fillings, Constraint, Filling};
+use crate::candidates::Candidates;
+use crate::data::{Coord, Grid, Reduction};
+use crate::error::DokuError;
+
+/// The kinds of clues outside of the grid that look along a row or column, reading the values from the clue inwards.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LineClueKind {
+    /// The number of buildings that can be seen from the clue, if every value is the height of a building and taller
+    /// buildings hide the shorter ones behind them
+    Skyscraper(u8),
+    /// The sum of the first X values, where X is the value next to the clue
+    XSum(u32),
+}
+
+impl LineClueKind {
+    /// Whether the values from the clue inwards satisfy the clue
+    fn holds(&self, values: &[u8]) -> bool {
+        match self {
+            LineClueKind::Skyscraper(count) => visible_buildings(values) == *count,
+            LineClueKind::XSum(sum) => match values.first() {
+                Some(x) if *x as usize <= values.len() => {
+                    values[..*x as usize].iter().map(|v| *v as u32).sum::<u32>() == *sum
+                }
+                _ => false,
+            },
+        }
+    }
+}
+
+/// The number of values that are bigger than all the values before them
+fn visible_buildings(values: &[u8]) -> u8 {
+    let mut tallest: u8 = 0;
+    let mut visible: u8 = 0;
+    for value in values {
+        if *value > tallest {
+            tallest = *value;
+            visible += 1;
+        }
+    }
+    visible
+}
+
+/// A skyscraper or X-sum clue, together with the cells of the row or column it looks along.
+#[derive(Debug)]
+pub struct LineClue {
+    kind: LineClueKind,
+    line: Vec<Coord>,
+}
+
+impl LineClue {
+    pub fn new(grid: &Grid, clue: OutsideCoord, kind: LineClueKind) -> Result<Self, DokuError> {
+        let line = clue.inward_cells(grid);
+        if line.is_empty() {
+            return Err(DokuError::InvalidRule(
+                "Outside clues should be next to the grid",
+            ));
+        }
+        Ok(Self { kind, line })
+    }
+
+    /// Tries every way to fill in the line from the clue inwards with the current candidates, up until the point where
+    /// the rest of the line no longer matters for the clue. Each cell can only keep the values that appear in one of the
+    /// fillings that satisfy the clue.
+    fn line_reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        let options: Vec<Candidates> = self
+            .line
+            .iter()
+            .map(|coord| grid.cell(coord).possible_values())
+            .collect();
+        let possible = search_fillings(grid, &self.line, &options, |assigned| {
+            if self.is_hopeless(grid, assigned) {
+                return Filling::Impossible;
+            }
+            if assigned.len() < self.line.len() && !self.is_settled(grid, assigned) {
+                return Filling::Incomplete;
+            }
+            let values = match self.kind {
+                LineClueKind::Skyscraper(_) => assigned,
+                LineClueKind::XSum(_) => &assigned[..assigned[0] as usize],
+            };
+            if self.kind.holds(values) {
+                Filling::Valid
+            } else {
+                Filling::Impossible
+            }
+        });
+        restrict_candidates(grid, &self.line, &possible)
+    }
+
+    /// Whether the values assigned so far decide the clue on their own: the tallest building hides everything behind
+    /// it, and an X-sum only looks at the first X values.
+    fn is_settled(&self, grid: &Grid, assigned: &[u8]) -> bool {
+        match self.kind {
+            LineClueKind::Skyscraper(_) => assigned.contains(&grid.size()),
+            LineClueKind::XSum(_) => assigned
+                .first()
+                .is_some_and(|x| *x as usize <= assigned.len()),
+        }
+    }
+
+    /// Whether the values assigned so far already rule out the clue, however the line continues
+    fn is_hopeless(&self, grid: &Grid, assigned: &[u8]) -> bool {
+        match self.kind {
+            LineClueKind::Skyscraper(count) => {
+                // Every building that's still to come can only be seen if it's taller than the tallest one so far
+                let visible = visible_buildings(assigned);
+                let tallest = assigned.iter().max().cloned().unwrap_or(0);
+                let remaining = self.line.len() - assigned.len();
+                let most_visible =
+                    visible as usize + remaining.min((grid.size() - tallest) as usize);
+                visible > count || most_visible < count as usize
+            }
+            LineClueKind::XSum(sum) => {
+                let Some(x) = assigned.first() else {
+                    return false;
+                };
+                if *x as usize > self.line.len() {
+                    return true;
+                }
+                // Each of the values still to come in the first X adds at least 1 and at most the grid size
+                let total: u32 = assigned.iter().map(|v| *v as u32).sum();
+                let remaining = (*x as usize).saturating_sub(assigned.len()) as u32;
+                total + remaining > sum || total + remaining * (grid.size() as u32) < sum
+            }
+        }
+    }
+}
+
+impl Constraint for LineClue {
+    fn touches(&self, coord: &Coord) -> bool {
+        self.line.contains(coord)
+    }
+
+    fn reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        self.line_reductions(grid)
+    }
+
+    fn validate(&self, grid: &Grid) -> Result<(), (&'static str, Coord)> {
+        let values: Vec<u8> = self
+            .line
+            .iter()
+            .map(|coord| grid.cell(coord).value.unwrap_or(0))
+            .collect();
+        if !self.kind.holds(&values) {
+            return Err((
+                "The values along a row or column do not match its outside clue",
+                self.line[0],
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraints::outside::Side;
+
+    #[test]
+    fn skyscrapers_seen_from_both_ends_of_a_row() {
+        let mut grid = Grid::new(9).unwrap();
+        let left = OutsideCoord::new(Side::Left, 0);
+        let right = OutsideCoord::new(Side::Right, 0);
+        grid.add_constraint(LineClue::new(&grid, left, LineClueKind::Skyscraper(1)).unwrap());
+        grid.add_constraint(LineClue::new(&grid, right, LineClueKind::Skyscraper(9)).unwrap());
+        grid.compute_candidates();
+        // Only the tallest building is visible from the left, and every building from the right
+        for x in 0..9 {
+            assert_eq!(
+                grid.cell(&Coord::new(x, 0)).candidates,
+                Candidates::from([9 - x])
+            );
+        }
+    }
+
+    #[test]
+    fn x_sum_of_forty_five_needs_the_whole_column() {
+        let mut grid = Grid::new(9).unwrap();
+        let top = OutsideCoord::new(Side::Top, 4);
+        grid.add_constraint(LineClue::new(&grid, top, LineClueKind::XSum(45)).unwrap());
+        grid.compute_candidates();
+        assert_eq!(
+            grid.cell(&Coord::new(4, 0)).candidates,
+            Candidates::from([9])
+        );
+        assert_eq!(
+            grid.cell(&Coord::new(4, 1)).candidates,
+            Candidates::from_iter(1..=8)
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use super::outside::OutsideCoord;
+use super::Constraint;
+use crate::candidates::Candidates;
+use crate::data::{Coord, Grid, Reduction};
+use crate::error::DokuError;
+
+/// The diagonal directions a Little Killer arrow can point in.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Diagonal {
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Diagonal {
+    pub fn step(&self) -> (i16, i16) {
+        match self {
+            Diagonal::UpLeft => (-1, -1),
+            Diagonal::UpRight => (1, -1),
+            Diagonal::DownLeft => (-1, 1),
+            Diagonal::DownRight => (1, 1),
+        }
+    }
+}
+
+/// A Little Killer clue is an arrow outside of the grid that points along a diagonal, starting at the cell next to the
+/// clue. The values on that diagonal add up to the clue's sum, and unlike in a killer cage they may repeat.
+#[derive(Debug)]
+pub struct LittleKiller {
+    coords: Vec<Coord>,
+    sum: u32,
+}
+
+impl LittleKiller {
+    pub fn new(
+        grid: &Grid,
+        clue: OutsideCoord,
+        direction: Diagonal,
+        sum: u32,
+    ) -> Result<Self, DokuError> {
+        if !clue.points_inward(direction.step()) {
+            return Err(DokuError::InvalidRule(
+                "Little killer arrows should point away from the side of the grid they're on",
+            ));
+        }
+        let coords = clue.cells_towards(grid, direction.step());
+        if coords.is_empty() {
+            return Err(DokuError::InvalidRule(
+                "Little killer arrows should point into the grid",
+            ));
+        }
+        Ok(Self { coords, sum })
+    }
+
+    /// Each cell has to hold a value that lets the other cells on the diagonal make up the rest of the sum, based on the
+    /// smallest and largest values they can still take.
+    fn range_reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        let values: Vec<Candidates> = self
+            .coords
+            .iter()
+            .map(|coord| grid.cell(coord).possible_values())
+            .collect();
+        let min: u32 = values.iter().map(|v| v.min().unwrap_or(0) as u32).sum();
+        let max: u32 = values.iter().map(|v| v.max().unwrap_or(0) as u32).sum();
+
+        let mut reductions: Vec<Reduction> = Vec::new();
+        for (coord, cell_values) in self.coords.iter().zip(values.iter()) {
+            let cell = grid.cell(coord);
+            if !cell.is_empty() {
+                continue;
+            }
+            let rest_min = min - cell_values.min().unwrap_or(0) as u32;
+            let rest_max = max - cell_values.max().unwrap_or(0) as u32;
+            for candidate in cell.candidates.iter() {
+                let value = candidate as u32;
+                if value + rest_min > self.sum || value + rest_max < self.sum {
+                    reductions.push(Reduction::new(*coord, candidate));
+                }
+            }
+        }
+        reductions
+    }
+}
+
+impl Constraint for LittleKiller {
+    fn touches(&self, coord: &Coord) -> bool {
+        self.coords.contains(coord)
+    }
+
+    fn reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        self.range_reductions(grid)
+    }
+
+    fn validate(&self, grid: &Grid) -> Result<(), (&'static str, Coord)> {
+        let sum: u32 = self
+            .coords
+            .iter()
+            .filter_map(|coord| grid.cell(coord).value)
+            .map(|v| v as u32)
+            .sum();
+        if sum != self.sum {
+            return Err((
+                "The values on a little killer diagonal do not add up to its sum",
+                self.coords[0],
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraints::outside::Side;
+
+    #[test]
+    fn little_killer_follows_its_diagonal() {
+        let mut grid = Grid::new(9).unwrap();
+        let little_killer = LittleKiller::new(
+            &grid,
+            OutsideCoord::new(Side::Top, 6),
+            Diagonal::DownRight,
+            22,
+        )
+        .unwrap();
+        assert_eq!(
+            little_killer.coords,
+            vec![Coord::new(6, 0), Coord::new(7, 1), Coord::new(8, 2)]
+        );
+        grid.add_constraint(little_killer);
+        grid.compute_candidates();
+        // The other two cells add up to at most 18, so each cell holds at least 4
+        assert_eq!(
+            grid.cell(&Coord::new(7, 1)).candidates,
+            Candidates::from_iter(4..=9)
+        );
+    }
+}
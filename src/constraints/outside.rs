@@ -0,0 +1,69 @@
+use crate::data::{Coord, Grid};
+
+/// The side of the grid a clue outside of the grid is written on.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Side {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+/// The position of a clue outside of the grid: the side of the grid it's on and the column (for the top and bottom) or
+/// row (for the left and right) it's next to. Top 0 is just above the top left cell, right 8 is just to the right of
+/// the bottom right cell of a 9x9 grid.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct OutsideCoord {
+    side: Side,
+    index: u8,
+}
+
+impl OutsideCoord {
+    pub fn new(side: Side, index: u8) -> Self {
+        Self { side, index }
+    }
+
+    /// The cell inside the grid right next to the clue
+    pub fn first_cell(&self, grid: &Grid) -> Coord {
+        match self.side {
+            Side::Top => Coord::new(self.index, 0),
+            Side::Bottom => Coord::new(self.index, grid.height() - 1),
+            Side::Left => Coord::new(0, self.index),
+            Side::Right => Coord::new(grid.width() - 1, self.index),
+        }
+    }
+
+    /// The cells in the clue's row or column, starting at the one next to the clue and moving away from it
+    pub fn inward_cells(&self, grid: &Grid) -> Vec<Coord> {
+        let step = match self.side {
+            Side::Top => (0, 1),
+            Side::Bottom => (0, -1),
+            Side::Left => (1, 0),
+            Side::Right => (-1, 0),
+        };
+        self.cells_towards(grid, step)
+    }
+
+    /// Whether moving by `step` takes you away from the clue's side of the grid, rather than along it or back out
+    pub fn points_inward(&self, step: (i16, i16)) -> bool {
+        match self.side {
+            Side::Top => step.1 > 0,
+            Side::Bottom => step.1 < 0,
+            Side::Left => step.0 > 0,
+            Side::Right => step.0 < 0,
+        }
+    }
+
+    /// The cells from the one next to the clue onwards in the direction of `step`, up until the edge of the grid
+    pub fn cells_towards(&self, grid: &Grid, step: (i16, i16)) -> Vec<Coord> {
+        let first = self.first_cell(grid);
+        let (mut x, mut y) = (first.x() as i16, first.y() as i16);
+        let mut coords: Vec<Coord> = Vec::new();
+        while let Some(coord) = grid.coord_at(x, y) {
+            coords.push(coord);
+            x += step.0;
+            y += step.1;
+        }
+        coords
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::BTreeSet;
+
+use super::{support_reductions, Constraint};
+use crate::data::{Coord, Grid, Reduction, Solution};
+use crate::error::DokuError;
+
+/// A relation between the values of two cells, such as the ones indicated by Kropki dots, XV sums or greater-than signs.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PairRelation {
+    /// The values differ by one (a white Kropki dot)
+    Consecutive,
+    /// One value is twice the other (a black Kropki dot)
+    Double,
+    /// The values add up to the given sum (an X for 10 or a V for 5)
+    Sum(u8),
+    /// The first value is bigger than the second
+    Greater,
+}
+
+impl PairRelation {
+    pub fn holds(&self, a: u8, b: u8) -> bool {
+        match self {
+            PairRelation::Consecutive => a.abs_diff(b) == 1,
+            PairRelation::Double => a as u16 == 2 * b as u16 || b as u16 == 2 * a as u16,
+            PairRelation::Sum(sum) => a as u16 + b as u16 == *sum as u16,
+            PairRelation::Greater => a > b,
+        }
+    }
+
+    /// Whether the relation can hold between two equal values
+    fn allows_equal(&self) -> bool {
+        match self {
+            PairRelation::Consecutive | PairRelation::Double | PairRelation::Greater => false,
+            PairRelation::Sum(_) => true,
+        }
+    }
+}
+
+/// The orthogonal neighbours of a coordinate that lie inside the grid
+fn orthogonal_neighbours(grid: &Grid, coord: &Coord) -> Vec<Coord> {
+    let (x, y) = (coord.x() as i16, coord.y() as i16);
+    [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+        .into_iter()
+        .filter_map(|(x, y)| grid.coord_at(x, y))
+        .collect()
+}
+
+/// Two cells whose values have to be in a given relation, like the two cells on either side of a Kropki dot. For
+/// relations that aren't symmetrical, such as `Greater`, the relation is between `a` and `b` in that order.
+#[derive(Debug)]
+pub struct Pair {
+    a: Coord,
+    b: Coord,
+    relation: PairRelation,
+}
+
+impl Pair {
+    /// Creates a pair of orthogonally adjacent cells
+    pub fn new(a: Coord, b: Coord, relation: PairRelation) -> Result<Self, DokuError> {
+        if a.x().abs_diff(b.x()) + a.y().abs_diff(b.y()) != 1 {
+            return Err(DokuError::InvalidRule(
+                "The cells of a pair must be orthogonal neighbours",
+            ));
+        }
+        Ok(Self { a, b, relation })
+    }
+
+    fn pair_reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        let mut reductions =
+            support_reductions(grid, &self.a, &self.b, |a, b| self.relation.holds(a, b));
+        reductions.extend(support_reductions(grid, &self.b, &self.a, |b, a| {
+            self.relation.holds(a, b)
+        }));
+        reductions
+    }
+}
+
+impl Constraint for Pair {
+    fn touches(&self, coord: &Coord) -> bool {
+        self.a == *coord || self.b == *coord
+    }
+
+    fn reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        self.pair_reductions(grid)
+    }
+
+    fn validate(&self, grid: &Grid) -> Result<(), (&'static str, Coord)> {
+        let a = grid.cell(&self.a).value.unwrap_or(0);
+        let b = grid.cell(&self.b).value.unwrap_or(0);
+        if !self.relation.holds(a, b) {
+            return Err((
+                "The values of a pair of cells are not in the required relation",
+                self.b,
+            ));
+        }
+        Ok(())
+    }
+
+    fn sees(&self, a: &Coord, b: &Coord) -> bool {
+        !self.relation.allows_equal() && self.touches(a) && self.touches(b)
+    }
+}
+
+/// None of the given relations may hold between two orthogonally adjacent cells, except for the listed pairs. This covers
+/// both the non-consecutive rule and the negative constraint of Kropki puzzles where all dots are given.
+#[derive(Debug)]
+pub struct ForbiddenNeighbours {
+    relations: Vec<PairRelation>,
+    except: BTreeSet<(Coord, Coord)>,
+}
+
+impl ForbiddenNeighbours {
+    pub fn new(relations: Vec<PairRelation>, except: &[(Coord, Coord)]) -> Self {
+        Self {
+            relations,
+            except: except
+                .iter()
+                .flat_map(|(a, b)| [(*a, *b), (*b, *a)])
+                .collect(),
+        }
+    }
+
+    fn neighbours(&self, grid: &Grid, coord: &Coord) -> Vec<Coord> {
+        orthogonal_neighbours(grid, coord)
+            .into_iter()
+            .filter(|neighbour| !self.except.contains(&(*coord, *neighbour)))
+            .collect()
+    }
+
+    fn allowed(&self, a: u8, b: u8) -> bool {
+        !self.relations.iter().any(|relation| relation.holds(a, b))
+    }
+
+    /// Removes the candidates that conflict with every value an orthogonal neighbour of one of the given cells can take
+    fn neighbour_reductions(&self, grid: &Grid, coords: &BTreeSet<Coord>) -> Vec<Reduction> {
+        let mut reductions: Vec<Reduction> = Vec::new();
+        for coord in coords {
+            for neighbour in self.neighbours(grid, coord) {
+                reductions.extend(support_reductions(grid, &neighbour, coord, |a, b| {
+                    self.allowed(a, b)
+                }));
+            }
+        }
+        reductions
+    }
+}
+
+impl Constraint for ForbiddenNeighbours {
+    fn initial_reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        let coords: BTreeSet<Coord> = grid
+            .cells()
+            .iter()
+            .filter(|cell| !cell.is_empty())
+            .map(|cell| cell.coord)
+            .collect();
+        self.neighbour_reductions(grid, &coords)
+    }
+
+    fn propagate(&self, grid: &Grid, solution: &Solution) -> Vec<Reduction> {
+        self.neighbour_reductions(grid, &BTreeSet::from([solution.coord()]))
+    }
+
+    fn propagate_reductions(&self, grid: &Grid, reductions: &[Reduction]) -> Vec<Reduction> {
+        let coords: BTreeSet<Coord> = reductions.iter().map(|r| r.coord()).collect();
+        self.neighbour_reductions(grid, &coords)
+    }
+
+    fn validate(&self, grid: &Grid) -> Result<(), (&'static str, Coord)> {
+        for cell in grid.cells().iter() {
+            let value = cell.value.unwrap_or(0);
+            for neighbour in self.neighbours(grid, &cell.coord) {
+                if !self.allowed(value, grid.cell(&neighbour).value.unwrap_or(0)) {
+                    return Err((
+                        "Two neighbouring cells have values that aren't allowed next to each other",
+                        cell.coord,
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::candidates::Candidates;
+
+    #[test]
+    fn black_dot_next_to_a_given_value() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.set_given_value(Coord::new(0, 0), 4);
+        grid.add_constraint(
+            Pair::new(Coord::new(0, 0), Coord::new(1, 0), PairRelation::Double).unwrap(),
+        );
+        grid.compute_candidates();
+        assert_eq!(
+            grid.cell(&Coord::new(1, 0)).candidates,
+            Candidates::from([2, 8])
+        );
+    }
+
+    #[test]
+    fn v_sum_and_greater_than_restrict_both_cells() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.add_constraint(
+            Pair::new(Coord::new(0, 0), Coord::new(1, 0), PairRelation::Sum(5)).unwrap(),
+        );
+        grid.add_constraint(
+            Pair::new(Coord::new(0, 0), Coord::new(0, 1), PairRelation::Greater).unwrap(),
+        );
+        grid.compute_candidates();
+        assert_eq!(
+            grid.cell(&Coord::new(0, 0)).candidates,
+            Candidates::from([2, 3, 4])
+        );
+        assert_eq!(
+            grid.cell(&Coord::new(1, 0)).candidates,
+            Candidates::from([1, 2, 3])
+        );
+        assert_eq!(
+            grid.cell(&Coord::new(0, 1)).candidates,
+            Candidates::from([1, 2, 3])
+        );
+
+        grid.apply_reductions(&[
+            Reduction::new(Coord::new(0, 1), 1),
+            Reduction::new(Coord::new(0, 1), 2),
+        ]);
+        assert_eq!(
+            grid.cell(&Coord::new(0, 0)).candidates,
+            Candidates::from([4])
+        );
+        assert_eq!(
+            grid.cell(&Coord::new(1, 0)).candidates,
+            Candidates::from([1])
+        );
+    }
+
+    #[test]
+    fn non_consecutive_removes_candidates_surrounded_by_consecutive_values() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.add_constraint(ForbiddenNeighbours::new(
+            vec![PairRelation::Consecutive],
+            &[],
+        ));
+        grid.compute_candidates();
+        let reductions: Vec<Reduction> = (1..=9)
+            .filter(|v| *v != 4 && *v != 6)
+            .map(|v| Reduction::new(Coord::new(1, 0), v))
+            .collect();
+        grid.apply_reductions(&reductions);
+        // Both 4 and 6 are consecutive to 5, so the neighbours of (1,0) can't be 5
+        assert!(!grid.cell(&Coord::new(0, 0)).candidates.contains(5));
+        assert!(!grid.cell(&Coord::new(1, 1)).candidates.contains(5));
+        assert!(grid.cell(&Coord::new(0, 1)).candidates.contains(5));
+    }
+}
@@ -0,0 +1,131 @@
+use super::{restrict_candidates, search_fillings, Constraint, Filling};
+use crate::candidates::Candidates;
+use crate::data::{Coord, Grid, Reduction};
+use crate::error::DokuError;
+
+/// A quadruple clue is a circle on the corner shared by four cells, holding up to four digits that all have to appear
+/// among those cells. A digit that's listed more than once has to appear that many times.
+#[derive(Debug)]
+pub struct Quadruple {
+    coords: Vec<Coord>,
+    digits: Vec<u8>,
+}
+
+impl Quadruple {
+    /// Creates a quadruple clue on the top left corner of the cell at `corner`, so the clue is shared by that cell and
+    /// the cells to its left, above it and diagonally up and to the left of it.
+    pub fn new(grid: &Grid, corner: Coord, digits: Vec<u8>) -> Result<Self, DokuError> {
+        if !(1..=4).contains(&digits.len()) {
+            return Err(DokuError::InvalidRule(
+                "A quadruple clue holds one to four digits",
+            ));
+        }
+        if !digits.iter().all(|d| grid.possible_values().contains(d)) {
+            return Err(DokuError::InvalidRule(
+                "Quadruple digits must be values that fit in the grid",
+            ));
+        }
+        let (x, y) = (corner.x() as i16, corner.y() as i16);
+        let coords: Vec<Coord> = [(x - 1, y - 1), (x, y - 1), (x - 1, y), (x, y)]
+            .into_iter()
+            .map(|(x, y)| grid.coord_at(x, y))
+            .collect::<Option<_>>()
+            .ok_or(DokuError::InvalidRule(
+                "Quadruple clues must be on a corner shared by four cells",
+            ))?;
+        Ok(Self { coords, digits })
+    }
+
+    /// Tries every way to fill in the four cells with their current candidates. Each cell can only keep the values that
+    /// appear in one of the fillings that contain all of the clue's digits. This also places a digit in the only cell
+    /// that's left for it.
+    fn quadruple_reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        let options: Vec<Candidates> = self
+            .coords
+            .iter()
+            .map(|coord| grid.cell(coord).possible_values())
+            .collect();
+        let possible = search_fillings(grid, &self.coords, &options, |assigned| {
+            if assigned.len() < self.coords.len() {
+                Filling::Incomplete
+            } else if self.holds(assigned) {
+                Filling::Valid
+            } else {
+                Filling::Impossible
+            }
+        });
+        restrict_candidates(grid, &self.coords, &possible)
+    }
+
+    /// Whether the values of the four cells contain every digit of the clue
+    fn holds(&self, values: &[u8]) -> bool {
+        let mut remaining = values.to_vec();
+        for digit in self.digits.iter() {
+            match remaining.iter().position(|v| v == digit) {
+                Some(index) => {
+                    remaining.swap_remove(index);
+                }
+                None => return false,
+            }
+        }
+        true
+    }
+}
+
+impl Constraint for Quadruple {
+    fn touches(&self, coord: &Coord) -> bool {
+        self.coords.contains(coord)
+    }
+
+    fn reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        self.quadruple_reductions(grid)
+    }
+
+    fn validate(&self, grid: &Grid) -> Result<(), (&'static str, Coord)> {
+        let values: Vec<u8> = self
+            .coords
+            .iter()
+            .map(|coord| grid.cell(coord).value.unwrap_or(0))
+            .collect();
+        if !self.holds(&values) {
+            return Err((
+                "The cells around a quadruple clue are missing one of its digits",
+                self.coords[3],
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn four_digits_fill_the_quadruple() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.add_constraint(Quadruple::new(&grid, Coord::new(1, 1), vec![1, 2, 3, 4]).unwrap());
+        grid.compute_candidates();
+        for coord in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            assert_eq!(
+                grid.cell(&Coord::new(coord.0, coord.1)).candidates,
+                Candidates::from([1, 2, 3, 4])
+            );
+        }
+    }
+
+    #[test]
+    fn last_remaining_cell_gets_the_digit() {
+        let mut grid = Grid::new(9).unwrap();
+        // The 5s in row 0 and columns 3 and 4 leave only the bottom left cell of the quadruple for its 5
+        grid.set_given_value(Coord::new(6, 0), 5);
+        grid.set_given_value(Coord::new(4, 5), 5);
+        grid.add_constraint(Quadruple::new(&grid, Coord::new(4, 1), vec![5, 7]).unwrap());
+        grid.compute_candidates();
+        assert_eq!(
+            grid.cell(&Coord::new(3, 1)).candidates,
+            Candidates::from([5])
+        );
+        assert!(!grid.cell(&Coord::new(4, 1)).candidates.contains(5));
+    }
+}
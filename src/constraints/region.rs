@@ -0,0 +1,39 @@
+use super::{remove_candidate, Constraint};
+use crate::candidates::Candidates;
+use crate::data::{Coord, Grid, Reduction, Region, Solution};
+
+/// Every region holds each of the possible values exactly once.
+impl Constraint for Region {
+    fn initial_reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        let coords = self.cell_coords();
+        grid.cells_for_region(self)
+            .filter_map(|cell| cell.value)
+            .flat_map(|value| remove_candidate(grid, coords.iter(), value))
+            .collect()
+    }
+
+    fn propagate(&self, grid: &Grid, solution: &Solution) -> Vec<Reduction> {
+        if !self.contains_coord(&solution.coord()) {
+            return Vec::new();
+        }
+        remove_candidate(grid, self.cell_coords().iter(), solution.value())
+    }
+
+    fn sees(&self, a: &Coord, b: &Coord) -> bool {
+        self.contains_coord(a) && self.contains_coord(b)
+    }
+
+    fn validate(&self, grid: &Grid) -> Result<(), (&'static str, Coord)> {
+        let mut found_values = Candidates::new();
+        for cell in grid.cells_for_region(self) {
+            if cell.is_empty() {
+                return Err(("A cell has no value.", cell.coord));
+            }
+            if !found_values.insert(cell.value.unwrap()) {
+                return Err(("A value occurs twice in a region", cell.coord));
+            }
+        }
+        assert_eq!(found_values.len(), grid.size() as usize);
+        Ok(())
+    }
+}
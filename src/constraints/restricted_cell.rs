@@ -0,0 +1,91 @@
+use super::Constraint;
+use crate::candidates::Candidates;
+use crate::data::{Coord, Grid, Reduction, Solution};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Restriction {
+    /// A shaded cell that holds an even value
+    Even,
+    /// A circled cell that holds an odd value
+    Odd,
+    /// A cell that can only hold one of the given values, e.g. pencil marks from a partially solved puzzle
+    OneOf(Candidates),
+}
+
+impl Restriction {
+    fn allows(&self, value: u8) -> bool {
+        match self {
+            Restriction::Even => value.is_multiple_of(2),
+            Restriction::Odd => !value.is_multiple_of(2),
+            Restriction::OneOf(values) => values.contains(value),
+        }
+    }
+}
+
+/// A single cell that can only hold some of the possible values.
+#[derive(Debug)]
+pub struct RestrictedCell {
+    coord: Coord,
+    restriction: Restriction,
+}
+
+impl RestrictedCell {
+    pub fn new(coord: Coord, restriction: Restriction) -> Self {
+        Self { coord, restriction }
+    }
+}
+
+impl Constraint for RestrictedCell {
+    fn initial_reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        let cell = grid.cell(&self.coord);
+        if !cell.is_empty() {
+            return Vec::new();
+        }
+        cell.candidates
+            .iter()
+            .filter(|candidate| !self.restriction.allows(*candidate))
+            .map(|candidate| Reduction::new(self.coord, candidate))
+            .collect()
+    }
+
+    fn propagate(&self, _grid: &Grid, _solution: &Solution) -> Vec<Reduction> {
+        // Candidates only ever get removed, so once they're restricted they stay that way
+        Vec::new()
+    }
+
+    fn validate(&self, grid: &Grid) -> Result<(), (&'static str, Coord)> {
+        match grid.cell(&self.coord).value {
+            Some(value) if self.restriction.allows(value) => Ok(()),
+            _ => Err(("A cell holds a value it isn't allowed to", self.coord)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restrictions_are_applied_to_candidates() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.add_constraint(RestrictedCell::new(Coord::new(0, 0), Restriction::Even));
+        grid.add_constraint(RestrictedCell::new(Coord::new(1, 0), Restriction::Odd));
+        grid.add_constraint(RestrictedCell::new(
+            Coord::new(2, 0),
+            Restriction::OneOf(Candidates::from([3, 4])),
+        ));
+        grid.compute_candidates();
+        assert_eq!(
+            grid.cell(&Coord::new(0, 0)).candidates,
+            Candidates::from([2, 4, 6, 8])
+        );
+        assert_eq!(
+            grid.cell(&Coord::new(1, 0)).candidates,
+            Candidates::from([1, 3, 5, 7, 9])
+        );
+        assert_eq!(
+            grid.cell(&Coord::new(2, 0)).candidates,
+            Candidates::from([3, 4])
+        );
+    }
+}
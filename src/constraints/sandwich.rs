@@ -0,0 +1,153 @@
+use super::cage::sum_options;
+use super::{restrict_candidates, Constraint};
+use crate::candidates::Candidates;
+use crate::data::{Coord, Grid, Reduction, Region};
+
+/// A sandwich clue outside of a row or column gives the sum of the values between the lowest and the highest value (1
+/// and 9 in a 9x9 grid) in that row or column.
+#[derive(Debug)]
+pub struct Sandwich {
+    line: Vec<Coord>,
+    sum: u32,
+}
+
+impl Sandwich {
+    /// Creates a sandwich clue for a row or column region
+    pub fn new(region: &Region, sum: u32) -> Self {
+        let mut line: Vec<Coord> = region.cell_coords().iter().copied().collect();
+        line.sort_by_key(|coord| (coord.x(), coord.y()));
+        Self { line, sum }
+    }
+
+    /// Tries every placement of the lowest and highest value along the line. For each placement that's still possible,
+    /// the cells in between have to make up the sum with the other values, and the cells outside can hold any of the
+    /// other values.
+    fn sandwich_reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        let low: u8 = 1;
+        let high: u8 = grid.size();
+        let values: Vec<Candidates> = self
+            .line
+            .iter()
+            .map(|coord| grid.cell(coord).possible_values())
+            .collect();
+        let mut possible: Vec<Candidates> = vec![Candidates::new(); self.line.len()];
+        let low_and_high = Candidates::from([low, high]);
+        for low_index in (0..self.line.len()).filter(|i| values[*i].contains(low)) {
+            for high_index in (0..self.line.len()).filter(|i| values[*i].contains(high)) {
+                if low_index == high_index {
+                    continue;
+                }
+                let inside = low_index.min(high_index) + 1..low_index.max(high_index);
+                let other_values: Vec<Candidates> = values
+                    .iter()
+                    .map(|cell_values| cell_values.difference(&low_and_high))
+                    .collect();
+                let inside_possible = if inside.is_empty() {
+                    if self.sum != 0 {
+                        continue;
+                    }
+                    Vec::new()
+                } else {
+                    let inside_possible = sum_options(
+                        grid,
+                        &self.line[inside.clone()],
+                        &other_values[inside.clone()],
+                        self.sum,
+                    );
+                    if inside_possible.iter().any(|values| values.is_empty()) {
+                        continue;
+                    }
+                    inside_possible
+                };
+
+                possible[low_index].insert(low);
+                possible[high_index].insert(high);
+                for (i, cell_values) in inside.clone().zip(inside_possible) {
+                    possible[i] = possible[i].union(&cell_values);
+                }
+                for i in (0..self.line.len())
+                    .filter(|i| *i != low_index && *i != high_index && !inside.contains(i))
+                {
+                    possible[i] = possible[i].union(&other_values[i]);
+                }
+            }
+        }
+
+        restrict_candidates(grid, &self.line, &possible)
+    }
+}
+
+impl Constraint for Sandwich {
+    fn touches(&self, coord: &Coord) -> bool {
+        self.line.contains(coord)
+    }
+
+    fn reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        self.sandwich_reductions(grid)
+    }
+
+    fn validate(&self, grid: &Grid) -> Result<(), (&'static str, Coord)> {
+        let values: Vec<u8> = self
+            .line
+            .iter()
+            .map(|coord| grid.cell(coord).value.unwrap_or(0))
+            .collect();
+        let low_index = values.iter().position(|v| *v == 1);
+        let high_index = values.iter().position(|v| *v == grid.size());
+        let (Some(low_index), Some(high_index)) = (low_index, high_index) else {
+            return Err((
+                "A sandwiched line is missing its lowest or highest value",
+                self.line[0],
+            ));
+        };
+        let inside = low_index.min(high_index) + 1..low_index.max(high_index);
+        let sum: u32 = values[inside].iter().map(|v| *v as u32).sum();
+        if sum != self.sum {
+            return Err((
+                "The values in a sandwich do not add up to its clue",
+                self.line[0],
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{RegionKind, Row};
+
+    #[test]
+    fn sandwich_of_zero_puts_lowest_and_highest_side_by_side() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.set_given_value(Coord::new(4, 0), 1);
+        let sandwich = Sandwich::new(grid.region(&RegionKind::Row(Row::new(0))).unwrap(), 0);
+        grid.add_constraint(sandwich);
+        grid.compute_candidates();
+        for x in [0, 1, 2, 6, 7, 8] {
+            assert!(!grid.cell(&Coord::new(x, 0)).candidates.contains(9));
+        }
+        assert!(grid.cell(&Coord::new(3, 0)).candidates.contains(9));
+        assert!(grid.cell(&Coord::new(5, 0)).candidates.contains(9));
+    }
+
+    #[test]
+    fn sandwich_of_thirty_five_spans_the_whole_row() {
+        let mut grid = Grid::new(9).unwrap();
+        let sandwich = Sandwich::new(grid.region(&RegionKind::Row(Row::new(0))).unwrap(), 35);
+        grid.add_constraint(sandwich);
+        grid.compute_candidates();
+        assert_eq!(
+            grid.cell(&Coord::new(0, 0)).candidates,
+            Candidates::from([1, 9])
+        );
+        assert_eq!(
+            grid.cell(&Coord::new(8, 0)).candidates,
+            Candidates::from([1, 9])
+        );
+        assert_eq!(
+            grid.cell(&Coord::new(4, 0)).candidates,
+            Candidates::from_iter(2..=8)
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::BTreeSet;
+
+use super::Constraint;
+use crate::candidates::Candidates;
+use crate::data::{Coord, Grid, Reduction};
+use crate::error::DokuError;
+
+/// A thermometer is a path of cells whose values strictly increase from the bulb (the first cell) onwards.
+#[derive(Debug)]
+pub struct Thermometer {
+    coords: Vec<Coord>,
+}
+
+impl Thermometer {
+    pub fn new(coords: Vec<Coord>) -> Result<Self, DokuError> {
+        if coords.iter().collect::<BTreeSet<_>>().len() != coords.len() {
+            return Err(DokuError::InvalidRule(
+                "A thermometer can't pass through the same cell twice",
+            ));
+        }
+        Ok(Self { coords })
+    }
+
+    /// Each cell has to be bigger than the smallest value the cell before it can hold, and smaller than the biggest value
+    /// the cell after it can hold. Any candidates outside of those bounds are removed.
+    fn bounds_reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        let values: Vec<Candidates> = self
+            .coords
+            .iter()
+            .map(|coord| grid.cell(coord).possible_values())
+            .collect();
+
+        let mut lows: Vec<u8> = Vec::with_capacity(self.coords.len());
+        let mut low: u8 = 0;
+        for cell_values in values.iter() {
+            low = cell_values.iter().find(|&v| v > low).unwrap_or(u8::MAX);
+            lows.push(low);
+        }
+        let mut highs: Vec<u8> = vec![0; self.coords.len()];
+        let mut high: u8 = u8::MAX;
+        for (i, cell_values) in values.iter().enumerate().rev() {
+            high = cell_values.iter().filter(|&v| v < high).max().unwrap_or(0);
+            highs[i] = high;
+        }
+
+        let mut reductions: Vec<Reduction> = Vec::new();
+        for (i, coord) in self.coords.iter().enumerate() {
+            let cell = grid.cell(coord);
+            if !cell.is_empty() {
+                continue;
+            }
+            for candidate in cell.candidates.iter() {
+                if candidate < lows[i] || candidate > highs[i] {
+                    reductions.push(Reduction::new(*coord, candidate));
+                }
+            }
+        }
+        reductions
+    }
+}
+
+impl Constraint for Thermometer {
+    fn touches(&self, coord: &Coord) -> bool {
+        self.coords.contains(coord)
+    }
+
+    fn reductions(&self, grid: &Grid) -> Vec<Reduction> {
+        self.bounds_reductions(grid)
+    }
+
+    fn sees(&self, a: &Coord, b: &Coord) -> bool {
+        // Values on a thermometer strictly increase, so they're all different
+        self.coords.contains(a) && self.coords.contains(b)
+    }
+
+    fn validate(&self, grid: &Grid) -> Result<(), (&'static str, Coord)> {
+        for pair in self.coords.windows(2) {
+            if grid.cell(&pair[0]).value >= grid.cell(&pair[1]).value {
+                return Err((
+                    "A value on a thermometer is not bigger than the one before it",
+                    pair[1],
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thermometer_restricts_candidates_by_position() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.add_constraint(
+            Thermometer::new(vec![Coord::new(0, 0), Coord::new(1, 0), Coord::new(2, 0)]).unwrap(),
+        );
+        grid.compute_candidates();
+        assert_eq!(
+            grid.cell(&Coord::new(0, 0)).candidates,
+            Candidates::from_iter(1..=7)
+        );
+        assert_eq!(
+            grid.cell(&Coord::new(1, 0)).candidates,
+            Candidates::from_iter(2..=8)
+        );
+        assert_eq!(
+            grid.cell(&Coord::new(2, 0)).candidates,
+            Candidates::from_iter(3..=9)
+        );
+
+        grid.apply_reductions(&[
+            Reduction::new(Coord::new(2, 0), 9),
+            Reduction::new(Coord::new(2, 0), 8),
+        ]);
+        assert_eq!(
+            grid.cell(&Coord::new(1, 0)).candidates,
+            Candidates::from_iter(2..=6)
+        );
+        assert_eq!(
+            grid.cell(&Coord::new(0, 0)).candidates,
+            Candidates::from_iter(1..=5)
+        );
+    }
+}
@@ -1,8 +1,10 @@
 use core::fmt::Debug;
-use std::{
-    collections::{HashMap, HashSet},
-    ops::RangeInclusive,
-};
+use std::{cell::OnceCell, collections::BTreeSet, ops::RangeInclusive};
+
+use crate::candidates::Candidates;
+use crate::constraints::cage::{Cage, CageOperation};
+use crate::constraints::Constraint;
+use crate::error::DokuError;
 
 #[derive(Debug, Copy, Clone, PartialEq, Hash, Eq)]
 pub struct Coord {
@@ -14,6 +16,27 @@ impl Coord {
     pub fn new(x: u8, y: u8) -> Self {
         Self { x, y }
     }
+
+    pub fn x(&self) -> u8 {
+        self.x
+    }
+
+    pub fn y(&self) -> u8 {
+        self.y
+    }
+}
+
+/// Coordinates are ordered row by row, the same way the cells of a grid are stored and printed.
+impl Ord for Coord {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.y, self.x).cmp(&(other.y, other.x))
+    }
+}
+
+impl PartialOrd for Coord {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl std::fmt::Display for Coord {
@@ -25,7 +48,7 @@ impl std::fmt::Display for Coord {
 #[derive(Debug)]
 pub struct Cell {
     pub coord: Coord,
-    pub candidates: HashSet<u8>,
+    pub candidates: Candidates,
     pub value: Option<u8>,
     is_given: bool,
 }
@@ -34,8 +57,17 @@ impl Cell {
     pub fn is_empty(&self) -> bool {
         self.value.is_none()
     }
+
+    /// The values this cell can still take: its value if it has one, or its candidates otherwise.
+    pub fn possible_values(&self) -> Candidates {
+        match self.value {
+            Some(v) => Candidates::from([v]),
+            None => self.candidates,
+        }
+    }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Solution {
     coord: Coord,
     value: u8,
@@ -45,6 +77,14 @@ impl Solution {
     pub fn new(coord: Coord, value: u8) -> Self {
         Self { coord, value }
     }
+
+    pub fn coord(&self) -> Coord {
+        self.coord
+    }
+
+    pub fn value(&self) -> u8 {
+        self.value
+    }
 }
 
 impl std::fmt::Display for Solution {
@@ -53,6 +93,7 @@ impl std::fmt::Display for Solution {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Reduction {
     coord: Coord,
     candidate: u8,
@@ -62,6 +103,10 @@ impl Reduction {
     pub fn new(coord: Coord, candidate: u8) -> Self {
         Self { coord, candidate }
     }
+
+    pub fn coord(&self) -> Coord {
+        self.coord
+    }
 }
 
 impl std::fmt::Display for Reduction {
@@ -70,14 +115,30 @@ impl std::fmt::Display for Reduction {
     }
 }
 
+/// A row of `size` cells, starting at column `left` (which is only non-zero for sub-grids of a multi-grid puzzle)
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct Row {
     y: u8,
+    left: u8,
 }
 
+impl Row {
+    pub fn new(y: u8) -> Self {
+        Self { y, left: 0 }
+    }
+}
+
+/// A column of `size` cells, starting at row `top` (which is only non-zero for sub-grids of a multi-grid puzzle)
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct Column {
     x: u8,
+    top: u8,
+}
+
+impl Column {
+    pub fn new(x: u8) -> Self {
+        Self { x, top: 0 }
+    }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -86,222 +147,496 @@ pub struct Square {
     top_left: Coord,
 }
 
+/// The cells at the same position within each of the grid's squares, as in the disjoint groups variant
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct DisjointGroup {
+    size: u8,
+    offset: Coord,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum RegionKind {
     Row(Row),
     Column(Column),
     Square(Square),
+    DisjointGroup(DisjointGroup),
+    Diagonal,
+    AntiDiagonal,
 }
 
+/// The index of a region in the grid's `regions`, which never changes once the region has been added
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RegionId(usize);
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Region {
+    id: RegionId,
     kind: RegionKind,
-    coords: HashSet<Coord>,
+    coords: BTreeSet<Coord>,
+    /// The indices of the region's cells in the grid's `cells`, in row-major order
+    cell_indices: Vec<usize>,
 }
 impl Region {
     pub fn new(kind: RegionKind, grid: &Grid) -> Self {
         let mut region = Self {
+            // Assigned for real when the region gets added to the grid
+            id: RegionId(grid.regions.len()),
             kind,
-            coords: HashSet::with_capacity(grid.size as usize),
+            coords: BTreeSet::new(),
+            cell_indices: Vec::with_capacity(grid.size as usize),
         };
         region.compute_coords(grid);
+        // On multi-grid layouts a region's shape can reach into gaps between the sub-grids; those aren't cells
+        region.coords.retain(|c| grid.cell_index(c).is_some());
+        region.cell_indices = region
+            .coords
+            .iter()
+            .filter_map(|c| grid.cell_index(c))
+            .collect();
+        region.cell_indices.sort_unstable();
         region
     }
 
-    pub fn contains(self: &Self, cell: &Cell) -> bool {
-        self.contains_coord(&cell.coord)
+    pub fn id(&self) -> RegionId {
+        self.id
     }
 
-    pub fn contains_coord(self: &Self, coord: &Coord) -> bool {
+    pub fn contains_coord(&self, coord: &Coord) -> bool {
         self.coords.contains(coord)
     }
 
-    pub fn contains_coords(self: &Self, coords: &HashSet<Coord>) -> bool {
+    pub fn contains_coords(&self, coords: &BTreeSet<Coord>) -> bool {
         coords.is_subset(&self.coords)
     }
 
-    pub fn cell_coords(self: &Self) -> HashSet<Coord> {
-        self.coords.clone()
+    pub fn cell_coords(&self) -> &BTreeSet<Coord> {
+        &self.coords
     }
 
-    pub fn cells_with_candidate(self: &Self, grid: &Grid, candidate: u8) -> HashSet<Coord> {
-        let mut coords: HashSet<Coord> = HashSet::new();
+    pub fn cells_with_candidate(&self, grid: &Grid, candidate: u8) -> BTreeSet<Coord> {
+        let mut coords: BTreeSet<Coord> = BTreeSet::new();
         for cell in grid.cells_for_region(self) {
-            if cell.is_empty() && cell.candidates.contains(&candidate) {
+            if cell.is_empty() && cell.candidates.contains(candidate) {
                 coords.insert(cell.coord);
             }
         }
         coords
     }
 
-    fn compute_coords(self: &mut Self, grid: &Grid) {
+    pub fn is_square(&self) -> bool {
+        matches!(self.kind, RegionKind::Square(_))
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        matches!(self.kind, RegionKind::Diagonal | RegionKind::AntiDiagonal)
+    }
+
+    fn compute_coords(&mut self, grid: &Grid) {
         match self.kind {
             RegionKind::Row(row) => {
-                for x in 0..grid.size {
+                for x in row.left..row.left + grid.size {
                     self.coords.insert(Coord { x, y: row.y });
                 }
             }
             RegionKind::Column(column) => {
-                for y in 0..grid.size {
+                for y in column.top..column.top + grid.size {
                     self.coords.insert(Coord { x: column.x, y });
                 }
             }
             RegionKind::Square(subgrid) => {
+                // Extra squares near the edge wrap around to the other side of the grid (toroidal variants)
                 for y in 0..subgrid.size {
                     for x in 0..subgrid.size {
                         self.coords.insert(Coord {
-                            x: subgrid.top_left.x + x,
-                            y: subgrid.top_left.y + y,
+                            x: (subgrid.top_left.x + x) % grid.width,
+                            y: (subgrid.top_left.y + y) % grid.height,
+                        });
+                    }
+                }
+            }
+            RegionKind::DisjointGroup(group) => {
+                for y in 0..group.size {
+                    for x in 0..group.size {
+                        self.coords.insert(Coord {
+                            x: x * group.size + group.offset.x,
+                            y: y * group.size + group.offset.y,
                         });
                     }
                 }
             }
+            RegionKind::Diagonal => {
+                for i in 0..grid.size {
+                    self.coords.insert(Coord { x: i, y: i });
+                }
+            }
+            RegionKind::AntiDiagonal => {
+                for i in 0..grid.size {
+                    self.coords.insert(Coord {
+                        x: grid.size - 1 - i,
+                        y: i,
+                    });
+                }
+            }
         }
     }
 }
 
+/// Names regions the way solvers talk about them: "row 3", "column 7", "box 5". Boxes are numbered in reading order
+/// on a classic grid; boxes that don't line up with those (extra squares, boxes of other sub-grids) are named after
+/// their top left cell instead. Parts of a multi-grid puzzle mention where they start.
 impl std::fmt::Display for Region {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self.kind)
+        match self.kind {
+            RegionKind::Row(row) if row.left == 0 => write!(f, "row {}", row.y + 1),
+            RegionKind::Row(row) => write!(f, "row {} from column {}", row.y + 1, row.left + 1),
+            RegionKind::Column(column) if column.top == 0 => write!(f, "column {}", column.x + 1),
+            RegionKind::Column(column) => {
+                write!(f, "column {} from row {}", column.x + 1, column.top + 1)
+            }
+            RegionKind::Square(square) => {
+                let Coord { x, y } = square.top_left;
+                let per_row = square.size;
+                if x % per_row == 0
+                    && y % per_row == 0
+                    && x / per_row < per_row
+                    && y / per_row < per_row
+                {
+                    write!(f, "box {}", (y / per_row) * per_row + x / per_row + 1)
+                } else {
+                    write!(f, "box at r{}c{}", y + 1, x + 1)
+                }
+            }
+            RegionKind::DisjointGroup(group) => write!(
+                f,
+                "disjoint group {}",
+                group.offset.y * group.size + group.offset.x + 1
+            ),
+            RegionKind::Diagonal => write!(f, "diagonal"),
+            RegionKind::AntiDiagonal => write!(f, "anti-diagonal"),
+        }
     }
 }
 
 #[derive(Debug)]
 pub struct Grid {
-    pub size: u8,
-    pub cells: Vec<Cell>,
-    pub regions: Vec<Region>,
+    size: u8,
+    width: u8,
+    height: u8,
+    cells: Vec<Cell>,
+    /// The index in `cells` for each position on the canvas, if there is a cell at that position
+    cell_indices: Vec<Option<usize>>,
+    regions: Vec<Region>,
+    cages: Vec<Cage>,
+    /// Variant rules other than regions and cages
+    constraints: Vec<Box<dyn Constraint>>,
+    /// The indices in `regions` of the regions each cell belongs to, by cell index
+    cell_regions: Vec<Vec<RegionId>>,
+    /// The sorted indices of the cells each cell sees, by cell index. Worked out on first use, and again after a region,
+    /// cage or constraint has been added.
+    peers: OnceCell<Vec<Vec<usize>>>,
+}
+
+/// The families of regions that every (sub-)grid is made up of. Classic Sudokus use all of them, while Latin squares,
+/// Futoshiki and KenKen puzzles only have rows and columns.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum RegionFamily {
+    Rows,
+    Columns,
+    Boxes,
 }
 
-fn subgrid_size(size: u8) -> Result<u8, &'static str> {
+impl RegionFamily {
+    pub const ALL: [RegionFamily; 3] = [
+        RegionFamily::Rows,
+        RegionFamily::Columns,
+        RegionFamily::Boxes,
+    ];
+}
+
+fn subgrid_size(size: u8) -> Result<u8, DokuError> {
     let square_root = (size as f32).sqrt();
     let integer_part = square_root.trunc();
     if square_root == integer_part {
         Ok(integer_part as u8)
     } else {
-        Err("Sudokus of the given size cannot be square")
+        Err(DokuError::UnsupportedSize {
+            size,
+            reason: "Sudokus of the given size cannot be square",
+        })
     }
 }
 
 impl Grid {
-    pub fn new(size: u8) -> Self {
+    /// Creates a classic grid with rows, columns and boxes, which needs a square number as its size.
+    pub fn new(size: u8) -> Result<Self, DokuError> {
+        Self::with_subgrids(size, &[Coord::new(0, 0)], &RegionFamily::ALL)
+    }
+
+    /// Creates a grid made up of one or more (overlapping) sub-grids of `size` by `size` cells, such as a Samurai Sudoku.
+    /// Each sub-grid gets a region for each row, column and/or box, depending on the requested families. Boxes that are
+    /// shared between sub-grids are only added once.
+    pub fn with_subgrids(
+        size: u8,
+        subgrids: &[Coord],
+        families: &[RegionFamily],
+    ) -> Result<Self, DokuError> {
+        if size == 0 {
+            return Err(DokuError::UnsupportedSize {
+                size,
+                reason: "A grid needs at least one cell",
+            });
+        }
+        if subgrids.is_empty() {
+            return Err(DokuError::UnsupportedLayout(
+                "A grid needs at least one sub-grid",
+            ));
+        }
+        if size > Candidates::MAX_VALUE {
+            return Err(DokuError::UnsupportedSize {
+                size,
+                reason: "Grids can have at most 32 possible values",
+            });
+        }
+        let box_size = if families.contains(&RegionFamily::Boxes) {
+            Some(subgrid_size(size)?)
+        } else {
+            None
+        };
+        if subgrids
+            .iter()
+            .any(|s| s.x.checked_add(size).is_none() || s.y.checked_add(size).is_none())
+        {
+            return Err(DokuError::UnsupportedLayout(
+                "Sub-grids must fit on a canvas of 255 by 255 cells",
+            ));
+        }
+        let width = subgrids.iter().map(|s| s.x + size).max().unwrap_or(0);
+        let height = subgrids.iter().map(|s| s.y + size).max().unwrap_or(0);
         let mut grid = Grid {
             size,
-            cells: Vec::with_capacity(size as usize * size as usize),
+            width,
+            height,
+            cells: Vec::with_capacity(width as usize * height as usize),
+            cell_indices: Vec::with_capacity(width as usize * height as usize),
             regions: Vec::new(),
+            cages: Vec::new(),
+            constraints: Vec::new(),
+            cell_regions: Vec::new(),
+            peers: OnceCell::new(),
         };
 
-        for y in 0..size {
-            for x in 0..size {
+        for y in 0..height {
+            for x in 0..width {
+                let in_subgrid = subgrids
+                    .iter()
+                    .any(|s| (s.x..s.x + size).contains(&x) && (s.y..s.y + size).contains(&y));
+                if !in_subgrid {
+                    grid.cell_indices.push(None);
+                    continue;
+                }
+                grid.cell_indices.push(Some(grid.cells.len()));
+                grid.cell_regions.push(Vec::new());
                 grid.cells.push(Cell {
                     coord: Coord { x, y },
-                    candidates: HashSet::new(),
+                    candidates: Candidates::new(),
                     value: None,
                     is_given: false,
                 })
             }
+        }
 
-            // Since we're iterating over the size anyway we can set up our row & column regions here:
-            grid.regions
-                .push(Region::new(RegionKind::Row(Row { y }), &grid));
-            grid.regions
-                .push(Region::new(RegionKind::Column(Column { x: y }), &grid));
-        }
-
-        let subgrid_size = subgrid_size(size).expect("Expected a valid sudoku grid size");
-        for y in 0..subgrid_size {
-            for x in 0..subgrid_size {
-                grid.regions.push(Region::new(
-                    RegionKind::Square(Square {
-                        size: subgrid_size,
-                        top_left: Coord {
-                            x: x * subgrid_size,
-                            y: y * subgrid_size,
-                        },
-                    }),
-                    &grid,
-                ));
+        for subgrid in subgrids {
+            let mut kinds: Vec<RegionKind> = Vec::new();
+            for i in 0..size {
+                if families.contains(&RegionFamily::Rows) {
+                    kinds.push(RegionKind::Row(Row {
+                        y: subgrid.y + i,
+                        left: subgrid.x,
+                    }));
+                }
+                if families.contains(&RegionFamily::Columns) {
+                    kinds.push(RegionKind::Column(Column {
+                        x: subgrid.x + i,
+                        top: subgrid.y,
+                    }));
+                }
+            }
+            if let Some(subgrid_size) = box_size {
+                for y in 0..subgrid_size {
+                    for x in 0..subgrid_size {
+                        kinds.push(RegionKind::Square(Square {
+                            size: subgrid_size,
+                            top_left: Coord {
+                                x: subgrid.x + x * subgrid_size,
+                                y: subgrid.y + y * subgrid_size,
+                            },
+                        }));
+                    }
+                }
+            }
+            for kind in kinds {
+                if grid.region(&kind).is_none() {
+                    let region = Region::new(kind, &grid);
+                    grid.add_region(region);
+                }
             }
         }
 
-        grid
+        Ok(grid)
     }
 
-    fn coord_to_cell_index(self: &Self, coord: &Coord) -> usize {
-        (coord.x + self.size * coord.y) as usize
+    fn coord_to_cell_index(&self, coord: &Coord) -> usize {
+        self.cell_index(coord)
+            .expect("Coord should be a cell of the grid")
     }
 
-    pub fn set_given_value(self: &mut Self, coord: Coord, value: u8) {
+    fn cell_index(&self, coord: &Coord) -> Option<usize> {
+        if coord.x >= self.width || coord.y >= self.height {
+            return None;
+        }
+        self.cell_indices[coord.x as usize + coord.y as usize * self.width as usize]
+    }
+
+    /// The coordinate at the given position, if there is a cell there. Positions may lie outside of the canvas, which is
+    /// convenient when stepping from one cell to the next.
+    pub fn coord_at(&self, x: i16, y: i16) -> Option<Coord> {
+        if !(0..self.width as i16).contains(&x) || !(0..self.height as i16).contains(&y) {
+            return None;
+        }
+        self.cell_indices[x as usize + y as usize * self.width as usize]
+            .map(|index| self.cells[index].coord)
+    }
+
+    pub fn set_given_value(&mut self, coord: Coord, value: u8) {
         let index = self.coord_to_cell_index(&coord);
         self.cells[index].value = Some(value);
         self.cells[index].is_given = true;
     }
 
-    fn regions_for_cell(self: &Self, cell: &Cell) -> Vec<&Region> {
-        self.regions.iter().filter(|r| r.contains(cell)).collect()
+    /// The regions that hold all of the given cells.
+    pub fn regions_for_coords<'a>(
+        &'a self,
+        coords: &'a BTreeSet<Coord>,
+    ) -> impl Iterator<Item = RegionId> + 'a {
+        coords
+            .iter()
+            .next()
+            .into_iter()
+            .flat_map(|coord| self.regions_for_coord(coord))
+            .filter(|&id| self.region_by_id(id).contains_coords(coords))
     }
 
-    pub fn regions_for_coord(self: &Self, coord: &Coord) -> Vec<Region> {
-        self.regions
+    /// The regions the cell belongs to.
+    pub fn regions_for_coord(&self, coord: &Coord) -> impl Iterator<Item = RegionId> + '_ {
+        self.cell_regions[self.coord_to_cell_index(coord)]
             .iter()
-            .filter(|r| r.contains_coord(coord))
-            .cloned()
-            .collect()
+            .copied()
     }
 
-    pub fn regions_for_coords(self: &Self, coords: &HashSet<Coord>) -> Vec<&Region> {
-        self.regions
-            .iter()
-            .filter(|r| r.contains_coords(coords))
-            .collect()
+    pub fn region_by_id(&self, id: RegionId) -> &Region {
+        &self.regions[id.0]
     }
 
-    pub fn cells_for_region(self: &Self, region: &Region) -> Vec<&Cell> {
-        let coords = region.cell_coords();
-        self.cells
-            .iter()
-            .filter(|cell| coords.contains(&cell.coord))
-            .collect()
+    /// Two different cells "see" each other if some constraint keeps them from holding the same value, e.g. because they
+    /// share a region or a cage, or because they're a knight's move apart in an anti-knight puzzle.
+    pub fn sees(&self, a: &Coord, b: &Coord) -> bool {
+        let b_index = self.coord_to_cell_index(b);
+        self.peer_indices(a).binary_search(&b_index).is_ok()
     }
-    fn cells_for_region_mut(self: &mut Self, region: &Region) -> Vec<&mut Cell> {
-        let coords = region.cell_coords();
-        self.cells
-            .iter_mut()
-            .filter(|cell| coords.contains(&cell.coord))
-            .collect()
+
+    /// The cells that the given cell sees, in row-major order.
+    pub fn peers(&self, coord: &Coord) -> impl Iterator<Item = &Cell> {
+        self.peer_indices(coord).iter().map(|&i| &self.cells[i])
     }
 
-    fn grid_cell(self: &mut Self, coord: Coord) -> &mut Cell {
-        self.cells
-            .get_mut(coord.x as usize + coord.y as usize * self.size as usize)
-            .expect("Coord should be in bounds")
+    fn peer_indices(&self, coord: &Coord) -> &[usize] {
+        let peers = self.peers.get_or_init(|| self.compute_peers());
+        &peers[self.coord_to_cell_index(coord)]
     }
 
-    pub fn compute_candidates(self: &mut Self) {
-        let mut candidates: HashMap<Coord, HashSet<u8>> = HashMap::new();
-        for cell in self.cells.iter() {
-            if let Some(_) = cell.value {
-                continue;
+    /// Works out which cells see each other. Regions can tell from their cell lists alone, the other constraints are
+    /// asked about every pair of cells.
+    fn compute_peers(&self) -> Vec<Vec<usize>> {
+        let cell_count = self.cells.len();
+        let mut sees = vec![false; cell_count * cell_count];
+        for region in self.regions.iter() {
+            for &a in region.cell_indices.iter() {
+                for &b in region.cell_indices.iter() {
+                    sees[a * cell_count + b] = true;
+                }
             }
-            let regions = self.regions_for_cell(&cell);
-            // Start out with all candidates
-            let mut cell_candidates: HashSet<u8> = HashSet::from_iter(1..=self.size);
-            for region in regions.iter() {
-                let cells = self.cells_for_region(*region);
-                for v in cells.iter().filter_map(|v| v.value) {
-                    cell_candidates.remove(&v);
+        }
+        let others: Vec<&dyn Constraint> = self
+            .cages
+            .iter()
+            .map(|c| c as &dyn Constraint)
+            .chain(self.constraints.iter().map(|c| c.as_ref()))
+            .collect();
+        if !others.is_empty() {
+            for a in 0..cell_count {
+                for b in a + 1..cell_count {
+                    if sees[a * cell_count + b] {
+                        continue;
+                    }
+                    let (a_coord, b_coord) = (&self.cells[a].coord, &self.cells[b].coord);
+                    if others.iter().any(|c| c.sees(a_coord, b_coord)) {
+                        sees[a * cell_count + b] = true;
+                        sees[b * cell_count + a] = true;
+                    }
                 }
             }
-            candidates.insert(cell.coord, cell_candidates);
         }
+        (0..cell_count)
+            .map(|a| {
+                (0..cell_count)
+                    .filter(|&b| a != b && sees[a * cell_count + b])
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn region(&self, kind: &RegionKind) -> Option<&Region> {
+        self.regions.iter().find(|r| r.kind == *kind)
+    }
+
+    pub fn cell(&self, coord: &Coord) -> &Cell {
+        &self.cells[self.coord_to_cell_index(coord)]
+    }
+
+    /// The cells of the region, in row-major order.
+    pub fn cells_for_region<'a>(&'a self, region: &'a Region) -> impl Iterator<Item = &'a Cell> {
+        region.cell_indices.iter().map(|&i| &self.cells[i])
+    }
+    fn grid_cell(&mut self, coord: Coord) -> &mut Cell {
+        let index = self.coord_to_cell_index(&coord);
+        &mut self.cells[index]
+    }
+
+    /// All of the rules the grid's values have to obey: its regions, its cages and any other variant constraints.
+    pub fn all_constraints(&self) -> impl Iterator<Item = &dyn Constraint> {
+        self.regions
+            .iter()
+            .map(|r| r as &dyn Constraint)
+            .chain(self.cages.iter().map(|c| c as &dyn Constraint))
+            .chain(self.constraints.iter().map(|c| c.as_ref()))
+    }
+
+    pub fn compute_candidates(&mut self) {
+        // Start out with all candidates
+        let possible_values = Candidates::all(self.size);
         for cell in self.cells.iter_mut() {
-            if let Some(cell_candidates) = candidates.get(&cell.coord) {
-                cell.candidates = cell_candidates.clone();
+            if cell.is_empty() {
+                cell.candidates = possible_values;
             }
         }
+        let reductions: Vec<Reduction> = self
+            .all_constraints()
+            .flat_map(|c| c.initial_reductions(self))
+            .collect();
+        self.apply_reductions(&reductions);
     }
 
-    pub fn apply_solutions(self: &mut Self, solutions: &[Solution]) {
+    pub fn apply_solutions(&mut self, solutions: &[Solution]) {
         for solution in solutions {
             {
                 let cell = self.grid_cell(solution.coord);
@@ -309,58 +644,210 @@ impl Grid {
                 cell.candidates.clear();
             }
             {
-                // Remove candidate from all other cells that share a region with the cell
-                for region in self.regions_for_coord(&solution.coord) {
-                    for other_cell in self.cells_for_region_mut(&region) {
-                        other_cell.candidates.remove(&solution.value);
-                    }
-                }
+                // Let every constraint remove the candidates that are no longer possible
+                let reductions: Vec<Reduction> = self
+                    .all_constraints()
+                    .flat_map(|c| c.propagate(self, solution))
+                    .collect();
+                self.apply_reductions(&reductions);
             }
         }
     }
 
-    pub fn apply_reductions(self: &mut Self, reductions: &[Reduction]) {
-        for reduction in reductions {
-            let cell = self.grid_cell(reduction.coord);
-            cell.candidates.remove(&reduction.candidate);
+    pub fn apply_reductions(&mut self, reductions: &[Reduction]) {
+        let mut pending: Vec<Reduction> = reductions.to_vec();
+        // Removing candidates can allow constraints to remove even more candidates, so keep going until nothing changes
+        while !pending.is_empty() {
+            let applied: Vec<Reduction> = pending
+                .into_iter()
+                .filter(|r| self.grid_cell(r.coord).candidates.remove(r.candidate))
+                .collect();
+            if applied.is_empty() {
+                break;
+            }
+            pending = self
+                .all_constraints()
+                .flat_map(|c| c.propagate_reductions(self, &applied))
+                .collect();
         }
     }
 
-    pub fn add_extra_square(self: &mut Self, x: u8, y: u8) {
-        let square_size = subgrid_size(self.size)
-            .expect("Extra squares require a square grid");
+    /// The number of possible values, which is also the width and height of each (sub-)grid
+    pub fn size(&self) -> u8 {
+        self.size
+    }
+
+    /// The width of the canvas all sub-grids are placed on
+    pub fn width(&self) -> u8 {
+        self.width
+    }
+
+    /// The height of the canvas all sub-grids are placed on
+    pub fn height(&self) -> u8 {
+        self.height
+    }
+
+    /// The grid's cells in row-major order. They can only be changed through the grid, which keeps its caches of
+    /// regions and peers in line with them.
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    /// The grid's regions, indexed by their `RegionId`
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    pub fn cages(&self) -> &[Cage] {
+        &self.cages
+    }
+
+    /// Variant rules other than regions and cages
+    pub fn constraints(&self) -> &[Box<dyn Constraint>] {
+        &self.constraints
+    }
+
+    /// Adds an extra square region with its top left corner at the given position. Squares that don't fit wrap around
+    /// to the opposite edge of the grid.
+    pub fn add_extra_square(&mut self, x: u8, y: u8) -> Result<(), DokuError> {
+        let square_size = subgrid_size(self.size)?;
+        if x >= self.width || y >= self.height {
+            return Err(DokuError::UnsupportedLayout(
+                "The top left corner of an extra square must be inside the grid",
+            ));
+        }
         let square = Region::new(
             RegionKind::Square(Square {
                 size: square_size,
                 top_left: Coord::new(x, y),
             }),
-            &self,
+            self,
         );
-        self.regions.push(square);
+        if square.coords.len() != square_size as usize * square_size as usize {
+            return Err(DokuError::UnsupportedLayout(
+                "Extra squares must only cover cells of the grid",
+            ));
+        }
+        self.add_region(square);
+        Ok(())
+    }
+
+    /// Adds a region for each position within a square, containing the cells at that position in every square.
+    pub fn add_disjoint_groups(&mut self) -> Result<(), DokuError> {
+        let square_size = subgrid_size(self.size)?;
+        if (self.width, self.height) != (self.size, self.size) {
+            return Err(DokuError::UnsupportedLayout(
+                "Disjoint groups are only supported on single grids",
+            ));
+        }
+        for y in 0..square_size {
+            for x in 0..square_size {
+                let group = Region::new(
+                    RegionKind::DisjointGroup(DisjointGroup {
+                        size: square_size,
+                        offset: Coord::new(x, y),
+                    }),
+                    self,
+                );
+                self.add_region(group);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the two main diagonals as regions, as in X-Sudoku.
+    pub fn add_diagonals(&mut self) -> Result<(), DokuError> {
+        let diagonal = Region::new(RegionKind::Diagonal, self);
+        let anti_diagonal = Region::new(RegionKind::AntiDiagonal, self);
+        if [&diagonal, &anti_diagonal]
+            .iter()
+            .any(|d| d.coords.len() != self.size as usize)
+        {
+            return Err(DokuError::UnsupportedLayout(
+                "Diagonals must only cover cells of the grid",
+            ));
+        }
+        self.add_region(diagonal);
+        self.add_region(anti_diagonal);
+        Ok(())
+    }
+
+    fn add_region(&mut self, mut region: Region) {
+        region.id = RegionId(self.regions.len());
+        for &index in region.cell_indices.iter() {
+            self.cell_regions[index].push(region.id);
+        }
+        self.regions.push(region);
+        self.peers = OnceCell::new();
+    }
+
+    pub fn add_cage(&mut self, sum: u32, coords: BTreeSet<Coord>) {
+        self.cages.push(Cage::new(sum, coords));
+        self.peers = OnceCell::new();
     }
 
-    pub fn is_solved(self: &Self) -> bool {
+    /// Adds a KenKen cage, whose values may repeat unless they share a row or column.
+    pub fn add_arithmetic_cage(
+        &mut self,
+        operation: CageOperation,
+        target: u32,
+        coords: BTreeSet<Coord>,
+    ) -> Result<(), DokuError> {
+        self.cages
+            .push(Cage::arithmetic(operation, target, coords)?);
+        self.peers = OnceCell::new();
+        Ok(())
+    }
+
+    pub fn add_constraint(&mut self, constraint: impl Constraint + 'static) {
+        self.constraints.push(Box::new(constraint));
+        self.peers = OnceCell::new();
+    }
+
+    pub fn is_solved(&self) -> bool {
         !self.cells.iter().any(|c| c.value.is_none())
     }
 
-    pub fn validate(self: &Self) -> Result<(), (&'static str, Coord)> {
-        for region in self.regions.iter() {
-            let mut found_values: HashSet<u8> = HashSet::with_capacity(self.size as usize);
-            for cell in self.cells_for_region(region).iter() {
-                if cell.is_empty() {
-                    return Err(("A cell has no value.", cell.coord));
-                }
-                if !found_values.insert(cell.value.unwrap()) {
-                    return Err(("A value occurs twice in a region", cell.coord));
-                }
+    /// Checks that every cell has a value and that the values obey all of the grid's rules.
+    pub fn validate(&self) -> Result<(), DokuError> {
+        if let Some(cell) = self.cells.iter().find(|c| c.is_empty()) {
+            return Err(DokuError::Contradiction {
+                coord: cell.coord,
+                message: "A cell has no value.",
+            });
+        }
+        for constraint in self.all_constraints() {
+            constraint
+                .validate(self)
+                .map_err(|(message, coord)| DokuError::Contradiction { coord, message })?;
+        }
+        Ok(())
+    }
+
+    /// Looks for cells that see each other holding the same value and for empty cells that have run out of
+    /// candidates. Either means that one of the values or reductions so far was wrong, or that the puzzle has no
+    /// solution.
+    pub fn check_consistency(&self) -> Result<(), DokuError> {
+        for cell in self.cells.iter() {
+            let clash = match cell.value {
+                Some(value) => self.peers(&cell.coord).any(|p| p.value == Some(value)),
+                None => cell.candidates.is_empty(),
+            };
+            if clash {
+                return Err(DokuError::Contradiction {
+                    coord: cell.coord,
+                    message: match cell.value {
+                        Some(_) => "The value is also in a cell that this one sees.",
+                        None => "A cell has no candidates left.",
+                    },
+                });
             }
-            assert_eq!(found_values.len(), self.size as usize);
         }
         Ok(())
     }
 
-    pub fn possible_values(self: &Self) -> RangeInclusive<u8> {
-        return 1..=self.size;
+    pub fn possible_values(&self) -> RangeInclusive<u8> {
+        1..=self.size
     }
 }
 
@@ -373,7 +860,7 @@ mod tests {
 
     #[test]
     fn grid_of_nine_has_subgrids() {
-        let grid = Grid::new(9);
+        let grid = Grid::new(9).unwrap();
         let squares: Vec<&Region> = grid
             .regions
             .iter()
@@ -387,7 +874,7 @@ mod tests {
     }
     #[test]
     fn grid_of_sixteen_has_subgrids() {
-        let grid = Grid::new(16);
+        let grid = Grid::new(16).unwrap();
         let squares: Vec<&Region> = grid
             .regions
             .iter()
@@ -401,7 +888,10 @@ mod tests {
     }
     #[test]
     fn grid_of_twelve_does_not_have_subgrids() {
-        let grid = Grid::new(12);
+        let origin = [Coord::new(0, 0)];
+        assert!(Grid::with_subgrids(12, &origin, &RegionFamily::ALL).is_err());
+        let families = [RegionFamily::Rows, RegionFamily::Columns];
+        let grid = Grid::with_subgrids(12, &origin, &families).unwrap();
         let squares: Vec<&Region> = grid
             .regions
             .iter()
@@ -409,5 +899,168 @@ mod tests {
             .collect();
         let num_squares = squares.len();
         assert_eq!(num_squares, 0);
+        assert_eq!(grid.regions.len(), 24);
+    }
+    #[test]
+    fn samurai_grids_share_corner_squares() {
+        let subgrids = [
+            Coord::new(0, 0),
+            Coord::new(12, 0),
+            Coord::new(6, 6),
+            Coord::new(0, 12),
+            Coord::new(12, 12),
+        ];
+        let grid = Grid::with_subgrids(9, &subgrids, &RegionFamily::ALL).unwrap();
+        assert_eq!((grid.width, grid.height), (21, 21));
+        assert_eq!(grid.cells.len(), 5 * 81 - 4 * 9);
+        assert_eq!(grid.coord_at(9, 0), None);
+        assert_eq!(grid.coord_at(9, 7), Some(Coord::new(9, 7)));
+        let squares = grid.regions.iter().filter(|r| r.is_square()).count();
+        assert_eq!(squares, 5 * 9 - 4);
+        // The middle row of the top left sub-grid does not continue into the gap between sub-grids
+        let row = grid
+            .region(&RegionKind::Row(Row { y: 4, left: 0 }))
+            .unwrap();
+        assert!(row.contains_coord(&Coord::new(8, 4)));
+        assert!(!row.contains_coord(&Coord::new(9, 4)));
+    }
+    #[test]
+    fn extra_squares_wrap_around_the_edges() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.add_extra_square(7, 8).unwrap();
+        let square = grid.regions.last().unwrap();
+        assert_eq!(square.coords.len(), 9);
+        for coord in [(7, 8), (8, 8), (0, 8), (7, 0), (0, 1)] {
+            assert!(square.contains_coord(&Coord::new(coord.0, coord.1)));
+        }
+    }
+    #[test]
+    fn disjoint_groups_take_one_cell_from_each_square() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.add_disjoint_groups().unwrap();
+        let groups: Vec<&Region> = grid
+            .regions
+            .iter()
+            .filter(|r| matches!(r.kind, RegionKind::DisjointGroup(_)))
+            .collect();
+        assert_eq!(groups.len(), 9);
+        let group = groups[5];
+        assert!(group.contains_coord(&Coord::new(2, 1)));
+        assert!(group.contains_coord(&Coord::new(8, 7)));
+        for square in grid.regions.iter().filter(|r| r.is_square()) {
+            assert_eq!(square.coords.intersection(&group.coords).count(), 1);
+        }
+    }
+    #[test]
+    fn diagonals_cover_both_main_diagonals() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.add_diagonals().unwrap();
+        let diagonals: Vec<&Region> = grid.regions.iter().filter(|r| r.is_diagonal()).collect();
+        assert_eq!(diagonals.len(), 2);
+        assert!(diagonals[0].contains_coord(&Coord::new(0, 0)));
+        assert!(diagonals[0].contains_coord(&Coord::new(8, 8)));
+        assert!(diagonals[1].contains_coord(&Coord::new(8, 0)));
+        assert!(diagonals[1].contains_coord(&Coord::new(0, 8)));
+        assert!(!diagonals[1].contains_coord(&Coord::new(4, 3)));
+    }
+
+    #[test]
+    fn diagonals_must_stay_on_the_grid() {
+        // A single sub-grid shifted right leaves a column of gaps where the main diagonals start
+        let mut grid = Grid::with_subgrids(4, &[Coord::new(1, 0)], &RegionFamily::ALL).unwrap();
+        assert!(matches!(
+            grid.add_diagonals(),
+            Err(DokuError::UnsupportedLayout(_))
+        ));
+        assert!(grid.regions.iter().all(|r| !r.is_diagonal()));
+        // Regions only ever hold positions of real cells
+        for region in grid.regions.iter() {
+            assert!(region.coords.iter().all(|c| grid.cell_index(c).is_some()));
+        }
+    }
+
+    #[test]
+    fn peers_follow_regions_and_constraints() {
+        let mut grid = Grid::new(9).unwrap();
+        let centre = Coord::new(4, 4);
+        assert_eq!(grid.regions_for_coord(&centre).count(), 3);
+        // 8 others in the row, 8 in the column and 4 more in the box
+        assert_eq!(grid.peers(&centre).count(), 20);
+        assert!(!grid.sees(&centre, &Coord::new(0, 0)));
+
+        grid.add_diagonals().unwrap();
+        assert_eq!(grid.regions_for_coord(&centre).count(), 5);
+        assert!(grid.sees(&centre, &Coord::new(0, 0)));
+        assert!(!grid.sees(&centre, &centre));
+
+        grid.add_cage(3, BTreeSet::from([Coord::new(0, 1), Coord::new(1, 3)]));
+        assert!(grid.sees(&Coord::new(1, 3), &Coord::new(0, 1)));
+        let peers: Vec<Coord> = grid.peers(&Coord::new(1, 3)).map(|c| c.coord).collect();
+        assert!(peers.contains(&Coord::new(0, 1)));
+        assert!(peers
+            .windows(2)
+            .all(|w| (w[0].y, w[0].x) < (w[1].y, w[1].x)));
+    }
+
+    #[test]
+    fn region_cells_are_listed_in_row_major_order() {
+        let grid = Grid::new(9).unwrap();
+        let square = grid.regions.iter().find(|r| r.is_square()).unwrap();
+        let coords: Vec<Coord> = grid.cells_for_region(square).map(|c| c.coord).collect();
+        assert_eq!(coords.len(), 9);
+        assert_eq!(coords[0], Coord::new(0, 0));
+        assert_eq!(coords[1], Coord::new(1, 0));
+        assert_eq!(coords[3], Coord::new(0, 1));
+    }
+
+    #[test]
+    fn regions_have_stable_ids_and_names() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.add_extra_square(1, 1).unwrap();
+        grid.add_diagonals().unwrap();
+        for (index, region) in grid.regions.iter().enumerate() {
+            assert_eq!(grid.region_by_id(region.id()), region);
+            assert_eq!(region.id(), RegionId(index));
+        }
+        let names: Vec<String> = grid
+            .regions_for_coord(&Coord::new(4, 4))
+            .map(|id| grid.region_by_id(id).to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["row 5", "column 5", "box 5", "diagonal", "anti-diagonal"]
+        );
+        let extra = grid.region_by_id(RegionId(27));
+        assert_eq!(extra.to_string(), "box at r2c2");
+
+        let samurai = Grid::with_subgrids(
+            9,
+            &[Coord::new(0, 0), Coord::new(12, 12)],
+            &RegionFamily::ALL,
+        )
+        .unwrap();
+        let names: Vec<String> = samurai
+            .regions_for_coord(&Coord::new(13, 20))
+            .map(|id| samurai.region_by_id(id).to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "column 14 from row 13",
+                "row 21 from column 13",
+                "box at r19c13"
+            ]
+        );
+    }
+
+    #[test]
+    fn cells_with_candidate_come_in_row_major_order() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.compute_candidates();
+        let column = grid.region(&RegionKind::Column(Column::new(4))).unwrap();
+        let coords: Vec<Coord> = column.cells_with_candidate(&grid, 7).into_iter().collect();
+        assert_eq!(coords.len(), 9);
+        assert!(coords.iter().enumerate().all(|(y, c)| c.y() == y as u8));
+        assert!(Coord::new(8, 0) < Coord::new(0, 1));
     }
 }
@@ -0,0 +1,59 @@
+use crate::data::Coord;
+
+/// Everything that can go wrong while loading or solving a puzzle.
+#[derive(Debug)]
+pub enum DokuError {
+    /// The puzzle file couldn't be read.
+    Io { path: String, error: std::io::Error },
+    /// The puzzle file isn't written the way we expect. Lines and columns start counting at 1.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// We can't build grids of this size, e.g. because boxes need a square number.
+    UnsupportedSize { size: u8, reason: &'static str },
+    /// The regions asked for don't fit the grid, e.g. an extra square covering the gap between sub-grids.
+    UnsupportedLayout(&'static str),
+    /// A rule can't be set up as given, e.g. a quadruple clue on a corner at the edge of the grid.
+    InvalidRule(&'static str),
+    /// The values or candidates in the grid break one of its rules.
+    Contradiction { coord: Coord, message: &'static str },
+    /// None of the strategies could make any more progress after the given number of steps.
+    Unsolvable { steps: u32 },
+}
+
+impl std::fmt::Display for DokuError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DokuError::Io { path, error } => write!(f, "Could not read {path}: {error}"),
+            DokuError::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "Line {line}, column {column}: {message}"),
+            DokuError::UnsupportedSize { size, reason } => {
+                write!(f, "Grids of size {size} are not supported: {reason}")
+            }
+            DokuError::UnsupportedLayout(reason) => write!(f, "Unsupported layout: {reason}"),
+            DokuError::InvalidRule(reason) => write!(f, "Invalid rule: {reason}"),
+            DokuError::Contradiction { coord, message } => {
+                write!(f, "There was an error with the cell at {coord}: {message}")
+            }
+            DokuError::Unsolvable { steps } => write!(
+                f,
+                "No implemented strategies can further solve this board (gave up after {steps} {})",
+                if *steps == 1 { "step" } else { "steps" }
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DokuError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DokuError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
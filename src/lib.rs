@@ -0,0 +1,17 @@
+//! Doku is a Sudoku solver for classic puzzles and many of their variants. Load a puzzle with
+//! `puzzle::load_puzzle` (or build a `Grid` yourself) and hand it to `solve`.
+
+pub mod candidates;
+pub mod constraints;
+pub mod data;
+pub mod error;
+pub mod output;
+pub mod puzzle;
+pub mod solver;
+pub mod strategies;
+
+pub use candidates::Candidates;
+pub use data::{Cell, Coord, Grid};
+pub use error::DokuError;
+pub use solver::{solve, SolveOutcome, SolverConfig, Strategy};
+pub use strategies::{ReduceStrategy, SolveStrategy};
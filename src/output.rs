@@ -3,15 +3,29 @@ use std::io::stdout;
 
 pub fn output_grid(grid: &Grid) {
     let _lock = stdout().lock();
-    for y in 0..grid.size {
-        for x in 0..grid.size {
-            let cell = &grid.cells[(y * grid.size + x) as usize];
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            // Multi-grid puzzles have gaps in between their sub-grids
+            let Some(coord) = grid.coord_at(x as i16, y as i16) else {
+                print!("  ");
+                continue;
+            };
+            let cell = grid.cell(&coord);
+            // Cells on a diagonal region (X-Sudoku) are marked with an asterisk instead of the trailing space
+            let separator = if grid
+                .regions_for_coord(&coord)
+                .any(|id| grid.region_by_id(id).is_diagonal())
+            {
+                '*'
+            } else {
+                ' '
+            };
             match cell.value {
                 Some(v) => {
-                    print!("{v} ")
+                    print!("{v}{separator}")
                 }
                 None => {
-                    print!(". ")
+                    print!(".{separator}")
                 }
             }
         }
@@ -21,10 +35,9 @@ pub fn output_grid(grid: &Grid) {
 
 pub fn output_candidates(grid: &Grid) {
     let _lock = stdout().lock();
-    for cell in grid.cells.iter() {
+    for cell in grid.cells().iter() {
         if cell.is_empty() {
-            let mut candidate_vec: Vec<u8> = cell.candidates.iter().cloned().collect();
-            candidate_vec.sort();
+            let candidate_vec: Vec<u8> = cell.candidates.iter().collect();
             println!("{}: {:?}", cell.coord, candidate_vec);
         }
     }
@@ -0,0 +1,690 @@
+use crate::candidates::Candidates;
+use crate::constraints::anti_chess::{AntiChess, ChessMove};
+use crate::constraints::arrow::Arrow;
+use crate::constraints::cage::CageOperation;
+use crate::constraints::clone::CloneRegions;
+use crate::constraints::line::{Line, LineKind};
+use crate::constraints::line_clue::{LineClue, LineClueKind};
+use crate::constraints::little_killer::{Diagonal, LittleKiller};
+use crate::constraints::outside::{OutsideCoord, Side};
+use crate::constraints::pair::{ForbiddenNeighbours, Pair, PairRelation};
+use crate::constraints::quadruple::Quadruple;
+use crate::constraints::restricted_cell::{RestrictedCell, Restriction};
+use crate::constraints::sandwich::Sandwich;
+use crate::constraints::thermometer::Thermometer;
+use crate::data::{Column, Coord, Grid, RegionFamily, RegionKind, Row};
+use crate::error::DokuError;
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::str::FromStr;
+
+use regex::{Match, Regex};
+
+/// Reads a puzzle file and builds its grid, with the given values filled in and all of its rules added.
+pub fn load_puzzle(file_path: &str) -> Result<Grid, DokuError> {
+    let contents = fs::read_to_string(file_path).map_err(|error| DokuError::Io {
+        path: file_path.to_string(),
+        error,
+    })?;
+    parse_puzzle(&contents)
+}
+
+/// A line of the puzzle file, which knows where it is so that it can point at the mistakes in it.
+struct SourceLine<'a> {
+    number: usize,
+    text: &'a str,
+}
+
+impl<'a> SourceLine<'a> {
+    fn error(&self, column: usize, message: impl Into<String>) -> DokuError {
+        DokuError::Parse {
+            line: self.number,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// An error pointing at the start of part of the line that a regex matched
+    fn error_at(&self, m: &Match, message: impl Into<String>) -> DokuError {
+        self.error(m.start() + 1, message)
+    }
+
+    /// Points an error from setting up a rule, or from adding regions the grid's layout can't hold, at the part of the
+    /// line it's about
+    fn rule<T>(&self, m: &Match, result: Result<T, DokuError>) -> Result<T, DokuError> {
+        result.map_err(|error| match error {
+            DokuError::InvalidRule(reason) | DokuError::UnsupportedLayout(reason) => {
+                self.error_at(m, reason)
+            }
+            error => error,
+        })
+    }
+
+    fn number<T: FromStr>(&self, m: Match, message: &str) -> Result<T, DokuError> {
+        m.as_str().parse().map_err(|_| self.error_at(&m, message))
+    }
+
+    /// The space separated words in the matched text, each with the column it starts at
+    fn words(&self, m: Match<'a>) -> impl Iterator<Item = (usize, &'a str)> {
+        let mut column = m.start() + 1;
+        m.as_str().split(' ').filter_map(move |word| {
+            let start = column;
+            column += word.len() + 1;
+            (!word.is_empty()).then_some((start, word))
+        })
+    }
+
+    /// Parses a coordinate written as `x,y`, without checking that it's part of a grid
+    fn position(&self, column: usize, word: &str) -> Result<Coord, DokuError> {
+        let (x, y) = word
+            .split_once(',')
+            .ok_or_else(|| self.error(column, "Coordinates should be written as x,y"))?;
+        let x = x
+            .parse()
+            .map_err(|_| self.error(column, "The X coordinate must be a number"))?;
+        let y = y
+            .parse()
+            .map_err(|_| self.error(column, "The Y coordinate must be a number"))?;
+        Ok(Coord::new(x, y))
+    }
+
+    /// Parses a space separated list of coordinates such as `0,0 1,0 1,1`, which must all be cells of the grid
+    fn coords(&self, grid: &Grid, m: Match<'a>) -> Result<Vec<Coord>, DokuError> {
+        self.words(m)
+            .map(|(column, word)| {
+                let coord = self.position(column, word)?;
+                grid.coord_at(coord.x() as i16, coord.y() as i16)
+                    .ok_or_else(|| self.error(column, format!("{word} is not a cell of the grid")))
+            })
+            .collect()
+    }
+
+    fn coord(&self, grid: &Grid, m: Match<'a>) -> Result<Coord, DokuError> {
+        Ok(self.coords(grid, m)?[0])
+    }
+
+    /// Parses the position of a clue outside of the grid, given as the side of the grid and the row or column number
+    fn outside_coord(&self, side: Match, index: Match) -> Result<OutsideCoord, DokuError> {
+        let side = match side.as_str() {
+            "top" => Side::Top,
+            "bottom" => Side::Bottom,
+            "left" => Side::Left,
+            _ => Side::Right,
+        };
+        let index = self.number(
+            index,
+            "The row or column of an outside clue must be a number",
+        )?;
+        Ok(OutsideCoord::new(side, index))
+    }
+}
+
+/// Builds the grid described by the text of a puzzle file: a `size n` line, optional layout headers, the rows of the
+/// grid and then one line per extra rule, such as `cage 15 0,0 1,0` or `extra anti-knight`.
+pub fn parse_puzzle(contents: &str) -> Result<Grid, DokuError> {
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .map(|(index, text)| SourceLine {
+            number: index + 1,
+            text,
+        })
+        .peekable();
+    let first_line = lines.next().ok_or(DokuError::Parse {
+        line: 1,
+        column: 1,
+        message: "The puzzle is empty".to_string(),
+    })?;
+    let size: u8 = match Regex::new(r"^size (\d+)$")
+        .unwrap()
+        .captures(first_line.text)
+    {
+        Some(m) => first_line.number(m.get(1).unwrap(), "The size must be a number")?,
+        None => {
+            return Err(first_line.error(
+                1,
+                "The first line should be 'size n' where n is the width of the puzzle.",
+            ))
+        }
+    };
+
+    // Optional header lines: multi-grid puzzles such as Samurai Sudoku list the top left corner of each of their
+    // sub-grids, and puzzles without boxes (Latin squares, Futoshiki...) list the region families to build
+    let subgrid_regex = Regex::new(r"^subgrid (\d+,\d+)$").unwrap();
+    let regions_regex = Regex::new(r"^regions((?: (?:rows|columns|boxes))+)$").unwrap();
+    let mut subgrids: Vec<Coord> = Vec::new();
+    let mut families: Option<Vec<RegionFamily>> = None;
+    let mut last_line = first_line.number;
+    while let Some(line) = lines.peek() {
+        if let Some(m) = subgrid_regex.captures(line.text) {
+            let corner = m.get(1).unwrap();
+            subgrids.push(line.position(corner.start() + 1, corner.as_str())?);
+        } else if let Some(m) = regions_regex.captures(line.text) {
+            let parsed = m
+                .get(1)
+                .unwrap()
+                .as_str()
+                .split_whitespace()
+                .map(|family| match family {
+                    "rows" => RegionFamily::Rows,
+                    "columns" => RegionFamily::Columns,
+                    "boxes" => RegionFamily::Boxes,
+                    _ => unreachable!(),
+                })
+                .collect();
+            families = Some(parsed);
+        } else {
+            break;
+        }
+        last_line = line.number;
+        lines.next();
+    }
+    let mut grid = if subgrids.is_empty() && families.is_none() {
+        Grid::new(size)?
+    } else {
+        if subgrids.is_empty() {
+            subgrids.push(Coord::new(0, 0));
+        }
+        let families = families.unwrap_or(RegionFamily::ALL.to_vec());
+        Grid::with_subgrids(size, &subgrids, &families)?
+    };
+    for y in 0..grid.height() {
+        let Some(grid_line) = lines.next() else {
+            return Err(DokuError::Parse {
+                line: last_line + 1,
+                column: 1,
+                message: "There must be enough lines to build the full grid.".to_string(),
+            });
+        };
+        last_line = grid_line.number;
+        let mut width: u8 = 0;
+        let mut column = 1;
+        for c in grid_line.text.split(' ') {
+            let x = width;
+            width = width.saturating_add(1);
+            let start = column;
+            column += c.len() + 1;
+            if x >= grid.width() {
+                return Err(grid_line.error(
+                    start,
+                    format!("Grid lines should have {} values", grid.width()),
+                ));
+            }
+            // Positions in between the sub-grids of a multi-grid puzzle are marked with a dash
+            if grid.coord_at(x as i16, y as i16).is_none() {
+                if c != "-" {
+                    return Err(grid_line.error(
+                        start,
+                        "Positions outside of the sub-grids should be marked with '-'",
+                    ));
+                }
+                continue;
+            }
+            if c != "." {
+                let v: u8 = c
+                    .parse()
+                    .map_err(|_| grid_line.error(start, "The value should have been a number"))?;
+                if !grid.possible_values().contains(&v) {
+                    return Err(grid_line.error(
+                        start,
+                        format!("Values should be between 1 and {}", grid.size()),
+                    ));
+                }
+                grid.set_given_value(Coord::new(x, y), v);
+            }
+        }
+        if width != grid.width() {
+            return Err(grid_line.error(
+                column,
+                format!("Grid lines should have {} values", grid.width()),
+            ));
+        }
+    }
+
+    let extra_regex = Regex::new(r"^extra square (\d+),(\d+)$").unwrap();
+    let diagonals_regex = Regex::new(r"^extra diagonals$").unwrap();
+    let disjoint_groups_regex = Regex::new(r"^extra disjoint-groups$").unwrap();
+    let anti_chess_regex = Regex::new(r"^extra anti-(knight|king)$").unwrap();
+    let non_consecutive_regex = Regex::new(r"^extra non-consecutive$").unwrap();
+    let kropki_negative_regex = Regex::new(r"^extra kropki-negative$").unwrap();
+    let consecutive_negative_regex = Regex::new(r"^extra consecutive-negative$").unwrap();
+    let consecutive_regex = Regex::new(r"^consecutive (\d+,\d+) (\d+,\d+)$").unwrap();
+    let clone_regex = Regex::new(r"^clone((?: \d+,\d+)+) :((?: \d+,\d+)+)$").unwrap();
+    let kropki_regex = Regex::new(r"^kropki (white|black) (\d+,\d+) (\d+,\d+)$").unwrap();
+    let xv_regex = Regex::new(r"^xv (x|v) (\d+,\d+) (\d+,\d+)$").unwrap();
+    let greater_than_regex = Regex::new(r"^gt (\d+,\d+) (<|>) (\d+,\d+)$").unwrap();
+    let sandwich_regex = Regex::new(r"^sandwich (row|column) (\d+) (\d+)$").unwrap();
+    let line_clue_regex =
+        Regex::new(r"^(skyscraper|x-sum) (top|bottom|left|right) (\d+) (\d+)$").unwrap();
+    let little_killer_regex =
+        Regex::new(r"^little-killer (top|bottom|left|right) (\d+) (nw|ne|sw|se) (\d+)$").unwrap();
+    let line_regex =
+        Regex::new(r"^(whisper|renban|palindrome|region-sum)((?: \d+,\d+)+)$").unwrap();
+    let parity_regex = Regex::new(r"^(even|odd)((?: \d+,\d+)+)$").unwrap();
+    let quadruple_regex = Regex::new(r"^quadruple (\d+,\d+)((?: \d+)+)$").unwrap();
+    let candidates_regex = Regex::new(r"^candidates (\d+,\d+)((?: \d+)+)$").unwrap();
+    // The negative constraints apply to every pair of neighbours without a dot, so they can only be set up once all the
+    // dots are known
+    let mut kropki_negative = false;
+    let mut consecutive_negative = false;
+    let mut dots: Vec<(Coord, Coord)> = Vec::new();
+    // Killer cages only have a sum, KenKen cages have an operator after their target (e.g. `cage 12x 0,0 0,1`)
+    let cage_regex = Regex::new(r"^cage (\d+)([+x*/×÷−-])?((?: \d+,\d+)+)$").unwrap();
+    let thermo_regex = Regex::new(r"^thermo((?: \d+,\d+)+)$").unwrap();
+    let arrow_regex = Regex::new(r"^arrow((?: \d+,\d+)+) :((?: \d+,\d+)+)$").unwrap();
+    for line in lines {
+        let extra_line = line.text;
+        if extra_line.trim().is_empty() {
+            continue;
+        }
+        if let Some(m) = arrow_regex.captures(extra_line) {
+            let arrow = Arrow::new(
+                line.coords(&grid, m.get(1).unwrap())?,
+                line.coords(&grid, m.get(2).unwrap())?,
+            );
+            grid.add_constraint(line.rule(&m.get(1).unwrap(), arrow)?);
+            continue;
+        }
+        if let Some(m) = thermo_regex.captures(extra_line) {
+            let thermometer = Thermometer::new(line.coords(&grid, m.get(1).unwrap())?);
+            grid.add_constraint(line.rule(&m.get(1).unwrap(), thermometer)?);
+            continue;
+        }
+        if let Some(m) = cage_regex.captures(extra_line) {
+            let target: u32 = line.number(m.get(1).unwrap(), "The cage target must be a number")?;
+            let coords: BTreeSet<Coord> =
+                line.coords(&grid, m.get(3).unwrap())?.into_iter().collect();
+            match m.get(2) {
+                None => grid.add_cage(target, coords),
+                Some(operator) => {
+                    let operation = match operator.as_str() {
+                        "+" => CageOperation::Sum,
+                        "-" | "−" => CageOperation::Difference,
+                        "x" | "*" | "×" => CageOperation::Product,
+                        "/" | "÷" => CageOperation::Quotient,
+                        _ => unreachable!(),
+                    };
+                    line.rule(
+                        &operator,
+                        grid.add_arithmetic_cage(operation, target, coords),
+                    )?;
+                }
+            }
+            continue;
+        }
+        if let Some(m) = diagonals_regex.find(extra_line) {
+            line.rule(&m, grid.add_diagonals())?;
+            continue;
+        }
+        if let Some(m) = disjoint_groups_regex.find(extra_line) {
+            line.rule(&m, grid.add_disjoint_groups())?;
+            continue;
+        }
+        if let Some(m) = anti_chess_regex.captures(extra_line) {
+            let chess_move = match m.get(1).unwrap().as_str() {
+                "knight" => ChessMove::Knight,
+                _ => ChessMove::King,
+            };
+            grid.add_constraint(AntiChess::new(chess_move));
+            continue;
+        }
+        if non_consecutive_regex.is_match(extra_line) {
+            grid.add_constraint(ForbiddenNeighbours::new(
+                vec![PairRelation::Consecutive],
+                &[],
+            ));
+            continue;
+        }
+        if kropki_negative_regex.is_match(extra_line) {
+            kropki_negative = true;
+            continue;
+        }
+        if consecutive_negative_regex.is_match(extra_line) {
+            consecutive_negative = true;
+            continue;
+        }
+        if let Some(m) = consecutive_regex.captures(extra_line) {
+            let a = line.coord(&grid, m.get(1).unwrap())?;
+            let b = line.coord(&grid, m.get(2).unwrap())?;
+            let pair = line.rule(
+                &m.get(2).unwrap(),
+                Pair::new(a, b, PairRelation::Consecutive),
+            )?;
+            dots.push((a, b));
+            grid.add_constraint(pair);
+            continue;
+        }
+        if let Some(m) = clone_regex.captures(extra_line) {
+            let original = line.coords(&grid, m.get(1).unwrap())?;
+            let clone = line.coords(&grid, m.get(2).unwrap())?;
+            let clone_regions =
+                line.rule(&m.get(2).unwrap(), CloneRegions::new(original, clone))?;
+            grid.add_constraint(clone_regions);
+            continue;
+        }
+        if let Some(m) = kropki_regex.captures(extra_line) {
+            let relation = match m.get(1).unwrap().as_str() {
+                "white" => PairRelation::Consecutive,
+                _ => PairRelation::Double,
+            };
+            let a = line.coord(&grid, m.get(2).unwrap())?;
+            let b = line.coord(&grid, m.get(3).unwrap())?;
+            let pair = line.rule(&m.get(3).unwrap(), Pair::new(a, b, relation))?;
+            dots.push((a, b));
+            grid.add_constraint(pair);
+            continue;
+        }
+        if let Some(m) = sandwich_regex.captures(extra_line) {
+            let index: u8 = line.number(
+                m.get(2).unwrap(),
+                "The sandwich row or column must be a number",
+            )?;
+            let sum: u32 = line.number(m.get(3).unwrap(), "The sandwich sum must be a number")?;
+            let kind = match m.get(1).unwrap().as_str() {
+                "row" => RegionKind::Row(Row::new(index)),
+                _ => RegionKind::Column(Column::new(index)),
+            };
+            let region = grid.region(&kind).ok_or_else(|| {
+                line.error_at(
+                    &m.get(2).unwrap(),
+                    "Sandwich clues should be for a row or column of the grid",
+                )
+            })?;
+            let sandwich = Sandwich::new(region, sum);
+            grid.add_constraint(sandwich);
+            continue;
+        }
+        if let Some(m) = line_clue_regex.captures(extra_line) {
+            let clue = line.outside_coord(m.get(2).unwrap(), m.get(3).unwrap())?;
+            let value = m.get(4).unwrap();
+            let kind = match m.get(1).unwrap().as_str() {
+                "skyscraper" => LineClueKind::Skyscraper(
+                    line.number(value, "The skyscraper clue must be a number")?,
+                ),
+                _ => LineClueKind::XSum(line.number(value, "The X-sum clue must be a number")?),
+            };
+            let line_clue = line.rule(&m.get(3).unwrap(), LineClue::new(&grid, clue, kind))?;
+            grid.add_constraint(line_clue);
+            continue;
+        }
+        if let Some(m) = little_killer_regex.captures(extra_line) {
+            let clue = line.outside_coord(m.get(1).unwrap(), m.get(2).unwrap())?;
+            let direction = match m.get(3).unwrap().as_str() {
+                "nw" => Diagonal::UpLeft,
+                "ne" => Diagonal::UpRight,
+                "sw" => Diagonal::DownLeft,
+                _ => Diagonal::DownRight,
+            };
+            let sum: u32 =
+                line.number(m.get(4).unwrap(), "The little killer sum must be a number")?;
+            let little_killer = line.rule(
+                &m.get(3).unwrap(),
+                LittleKiller::new(&grid, clue, direction, sum),
+            )?;
+            grid.add_constraint(little_killer);
+            continue;
+        }
+        if let Some(m) = line_regex.captures(extra_line) {
+            let kind = match m.get(1).unwrap().as_str() {
+                "whisper" => LineKind::Whisper,
+                "renban" => LineKind::Renban,
+                "palindrome" => LineKind::Palindrome,
+                _ => LineKind::RegionSum,
+            };
+            let cells = line.coords(&grid, m.get(2).unwrap())?;
+            let path = line.rule(&m.get(2).unwrap(), Line::new(&grid, kind, cells))?;
+            grid.add_constraint(path);
+            continue;
+        }
+        if let Some(m) = parity_regex.captures(extra_line) {
+            let restriction = match m.get(1).unwrap().as_str() {
+                "even" => Restriction::Even,
+                _ => Restriction::Odd,
+            };
+            for coord in line.coords(&grid, m.get(2).unwrap())? {
+                grid.add_constraint(RestrictedCell::new(coord, restriction.clone()));
+            }
+            continue;
+        }
+        if let Some(m) = quadruple_regex.captures(extra_line) {
+            let corner = line.coord(&grid, m.get(1).unwrap())?;
+            let digits = m.get(2).unwrap();
+            let digits: Vec<u8> = line
+                .words(digits)
+                .map(|(column, digit)| {
+                    digit
+                        .parse()
+                        .map_err(|_| line.error(column, "Quadruple digits must be numbers"))
+                })
+                .collect::<Result<_, _>>()?;
+            let quadruple = line.rule(&m.get(1).unwrap(), Quadruple::new(&grid, corner, digits))?;
+            grid.add_constraint(quadruple);
+            continue;
+        }
+        if let Some(m) = candidates_regex.captures(extra_line) {
+            let coord = line.coord(&grid, m.get(1).unwrap())?;
+            let values: Candidates = line
+                .words(m.get(2).unwrap())
+                .map(|(column, v)| match v.parse::<u8>() {
+                    Ok(v) if grid.possible_values().contains(&v) => Ok(v),
+                    _ => Err(line.error(
+                        column,
+                        format!("Candidates must be numbers between 1 and {}", grid.size()),
+                    )),
+                })
+                .collect::<Result<Vec<u8>, _>>()?
+                .into_iter()
+                .collect();
+            grid.add_constraint(RestrictedCell::new(coord, Restriction::OneOf(values)));
+            continue;
+        }
+        if let Some(m) = xv_regex.captures(extra_line) {
+            let sum = match m.get(1).unwrap().as_str() {
+                "x" => 10,
+                _ => 5,
+            };
+            let a = line.coord(&grid, m.get(2).unwrap())?;
+            let b = line.coord(&grid, m.get(3).unwrap())?;
+            let pair = line.rule(&m.get(3).unwrap(), Pair::new(a, b, PairRelation::Sum(sum)))?;
+            grid.add_constraint(pair);
+            continue;
+        }
+        if let Some(m) = greater_than_regex.captures(extra_line) {
+            let a = line.coord(&grid, m.get(1).unwrap())?;
+            let b = line.coord(&grid, m.get(3).unwrap())?;
+            let (bigger, smaller) = match m.get(2).unwrap().as_str() {
+                ">" => (a, b),
+                _ => (b, a),
+            };
+            let pair = line.rule(
+                &m.get(3).unwrap(),
+                Pair::new(bigger, smaller, PairRelation::Greater),
+            )?;
+            grid.add_constraint(pair);
+            continue;
+        }
+        if let Some(m) = extra_regex.captures(extra_line) {
+            let square_x: u8 = line.number(
+                m.get(1).unwrap(),
+                "The extra square X coordinate must be a number",
+            )?;
+            let square_y: u8 = line.number(
+                m.get(2).unwrap(),
+                "The extra square Y coordinate must be a number",
+            )?;
+            line.rule(
+                &m.get(1).unwrap(),
+                grid.add_extra_square(square_x, square_y),
+            )?;
+            continue;
+        }
+        return Err(line.error(1, format!("Unknown rule '{extra_line}'")));
+    }
+    if kropki_negative {
+        grid.add_constraint(ForbiddenNeighbours::new(
+            vec![PairRelation::Consecutive, PairRelation::Double],
+            &dots,
+        ));
+    }
+    if consecutive_negative {
+        grid.add_constraint(ForbiddenNeighbours::new(
+            vec![PairRelation::Consecutive],
+            &dots,
+        ));
+    }
+
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 4x4 puzzle without any values, for trying out the rules that follow the grid
+    const EMPTY_4X4: &str = "size 4\n. . . .\n. . . .\n. . . .\n. . . .\n";
+
+    fn parse_error(contents: &str) -> (usize, usize, String) {
+        match parse_puzzle(contents) {
+            Err(DokuError::Parse {
+                line,
+                column,
+                message,
+            }) => (line, column, message),
+            other => panic!("Expected a parse error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_mistake() {
+        let (line, column, _) = parse_error("size 4\n1 2 . .\n. . x .\n");
+        assert_eq!((line, column), (3, 5));
+        let (line, column, _) = parse_error("size 4\n1 2 . .\n");
+        assert_eq!((line, column), (3, 1));
+        let (line, column, message) = parse_error(&format!("{EMPTY_4X4}thermo 0,0 4,0\n"));
+        assert_eq!((line, column), (6, 12));
+        assert_eq!(message, "4,0 is not a cell of the grid");
+        let (line, _, message) = parse_error(&format!("{EMPTY_4X4}\nextra anything\n"));
+        assert_eq!(line, 7);
+        assert_eq!(message, "Unknown rule 'extra anything'");
+    }
+
+    #[test]
+    fn rules_that_cant_be_set_up_are_parse_errors() {
+        let (line, column, message) = parse_error(&format!("{EMPTY_4X4}cage 1- 0,0 1,0 2,0\n"));
+        assert_eq!((line, column), (6, 7));
+        assert_eq!(
+            message,
+            "Difference and quotient cages must have exactly two cells"
+        );
+        for digits in ["0", "1 9"] {
+            let (_, _, message) = parse_error(&format!("{EMPTY_4X4}quadruple 1,1 {digits}\n"));
+            assert_eq!(
+                message,
+                "Quadruple digits must be values that fit in the grid"
+            );
+        }
+        let (_, column, message) = parse_error(&format!("{EMPTY_4X4}quadruple 0,1 1 2\n"));
+        assert_eq!(column, 11);
+        assert_eq!(
+            message,
+            "Quadruple clues must be on a corner shared by four cells"
+        );
+    }
+
+    #[test]
+    fn little_killer_arrows_must_point_into_the_grid() {
+        // The errors point at the direction
+        for (rule, direction) in [
+            ("little-killer top 1 ne 3", 21),
+            ("little-killer left 0 nw 1", 22),
+        ] {
+            let (line, column, _) = parse_error(&format!("{EMPTY_4X4}{rule}\n"));
+            assert_eq!((line, column), (6, direction), "{rule}");
+        }
+        assert!(parse_puzzle(&format!("{EMPTY_4X4}little-killer top 1 se 3\n")).is_ok());
+        assert!(parse_puzzle(&format!("{EMPTY_4X4}little-killer left 0 ne 1\n")).is_ok());
+    }
+
+    #[test]
+    fn dots_must_be_between_neighbouring_cells() {
+        for rule in [
+            "kropki white 0,0 3,3",
+            "consecutive 1,1 2,2",
+            "kropki black 0,0 0,2",
+        ] {
+            let (line, _, message) = parse_error(&format!("{EMPTY_4X4}{rule}\n"));
+            assert_eq!(line, 6, "{rule}");
+            assert_eq!(message, "The cells of a pair must be orthogonal neighbours");
+        }
+        assert!(parse_puzzle(&format!("{EMPTY_4X4}kropki white 0,0 0,1\n")).is_ok());
+    }
+
+    #[test]
+    fn xv_and_greater_than_signs_must_be_between_neighbouring_cells() {
+        for (rule, column) in [
+            ("xv x 0,0 1,1", 10),
+            ("gt 0,0 > 3,3", 10),
+            ("gt 2,0 < 0,0", 10),
+        ] {
+            let (line, at, message) = parse_error(&format!("{EMPTY_4X4}{rule}\n"));
+            assert_eq!((line, at), (6, column), "{rule}");
+            assert_eq!(message, "The cells of a pair must be orthogonal neighbours");
+        }
+        assert!(parse_puzzle(&format!("{EMPTY_4X4}xv v 0,0 1,0\ngt 1,1 < 1,2\n")).is_ok());
+    }
+
+    #[test]
+    fn paths_cant_repeat_a_cell() {
+        for (rule, message) in [
+            (
+                "renban 0,0 0,0",
+                "A line can't pass through the same cell twice",
+            ),
+            (
+                "thermo 0,0 1,0 0,0",
+                "A thermometer can't pass through the same cell twice",
+            ),
+        ] {
+            let (line, _, found) = parse_error(&format!("{EMPTY_4X4}{rule}\n"));
+            assert_eq!((line, found.as_str()), (6, message), "{rule}");
+        }
+    }
+
+    #[test]
+    fn layout_errors_point_at_their_line() {
+        let (line, column, message) = parse_error(&format!("{EMPTY_4X4}\nextra square 20,0\n"));
+        assert_eq!((line, column), (7, 14));
+        assert_eq!(
+            message,
+            "The top left corner of an extra square must be inside the grid"
+        );
+        let shifted = "size 4\nsubgrid 1,0\n- . . . .\n- . . . .\n- . . . .\n- . . . .\n";
+        let (line, column, _) = parse_error(&format!("{shifted}extra diagonals\n"));
+        assert_eq!((line, column), (7, 1));
+    }
+
+    #[test]
+    fn sub_grids_must_fit_on_the_canvas() {
+        assert!(matches!(
+            parse_puzzle("size 9\nsubgrid 250,0\n"),
+            Err(DokuError::UnsupportedLayout(_))
+        ));
+        assert!(matches!(
+            parse_puzzle("size 4\nsubgrid 0,0\nsubgrid 0,252\n"),
+            Err(DokuError::UnsupportedLayout(_))
+        ));
+    }
+
+    #[test]
+    fn unsupported_sizes_are_reported() {
+        assert!(matches!(
+            parse_puzzle("size 12\n"),
+            Err(DokuError::UnsupportedSize { size: 12, .. })
+        ));
+        assert!(matches!(
+            parse_puzzle("size 6\nregions rows columns\n. . . . . .\n. . . . . .\n. . . . . .\n. . . . . .\n. . . . . .\n. . . . . .\nextra disjoint-groups\n"),
+            Err(DokuError::UnsupportedSize { size: 6, .. })
+        ));
+    }
+}
@@ -0,0 +1,230 @@
+use crate::data::Grid;
+use crate::error::DokuError;
+use crate::output;
+use crate::strategies::innies_outies::InniesOuties;
+use crate::strategies::killer_cage::KillerCage;
+use crate::strategies::naked_pair::NakedPair;
+use crate::strategies::region_intersection::RegionIntersection;
+use crate::strategies::simple_link::SimpleLink;
+use crate::strategies::single::Single;
+use crate::strategies::{ReduceStrategy, SolveStrategy};
+
+/// The strategies the solver knows about. `Strategy::ALL` lists them in the order they're tried by default, from the
+/// cheapest to the most expensive one.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Strategy {
+    Single,
+    KillerCage,
+    RegionIntersection,
+    NakedPair,
+    InniesOuties,
+    SimpleLink,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 6] = [
+        Strategy::Single,
+        Strategy::KillerCage,
+        Strategy::RegionIntersection,
+        Strategy::NakedPair,
+        Strategy::InniesOuties,
+        Strategy::SimpleLink,
+    ];
+
+    /// Applies the strategy once, returning whether it found anything. When `verbose`, whatever it found is printed.
+    fn apply(&self, grid: &mut Grid, verbose: bool) -> bool {
+        match self {
+            Strategy::Single => report("solutions", Single::try_solution(grid), verbose),
+            Strategy::KillerCage => report("reductions", KillerCage::try_reduction(grid), verbose),
+            Strategy::RegionIntersection => report(
+                "reductions",
+                RegionIntersection::try_reduction(grid),
+                verbose,
+            ),
+            Strategy::NakedPair => report("reductions", NakedPair::try_reduction(grid), verbose),
+            Strategy::InniesOuties => {
+                report("reductions", InniesOuties::try_reduction(grid), verbose)
+            }
+            Strategy::SimpleLink => report("reductions", SimpleLink::try_reduction(grid), verbose),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Strategy::Single => "singles",
+            Strategy::KillerCage => "killer cages",
+            Strategy::RegionIntersection => "intersections",
+            Strategy::NakedPair => "naked pair reduction",
+            Strategy::InniesOuties => "innies and outies",
+            Strategy::SimpleLink => "simple links",
+        }
+    }
+
+    fn nothing_found(&self) -> &'static str {
+        match self {
+            Strategy::Single => "No singles found.",
+            Strategy::KillerCage => "No killer cage reductions found.",
+            Strategy::RegionIntersection => "No intersections found.",
+            Strategy::NakedPair => "No naked pairs found.",
+            Strategy::InniesOuties => "No innies or outies found.",
+            Strategy::SimpleLink => "No simple links found.",
+        }
+    }
+}
+
+fn report<T: std::fmt::Display>(kind: &str, found: Vec<T>, verbose: bool) -> bool {
+    if verbose && !found.is_empty() {
+        println!("### Found {kind}:");
+        found.iter().for_each(|f| println!("{f}"));
+    }
+    !found.is_empty()
+}
+
+/// How `solve` goes about solving a grid.
+#[derive(Debug, Clone)]
+pub struct SolverConfig {
+    /// The strategies to use. Each step tries them in this order and starts over as soon as one of them finds something.
+    pub strategies: Vec<Strategy>,
+    /// Whether to print the board, the candidates and everything the strategies find along the way
+    pub verbose: bool,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            strategies: Strategy::ALL.to_vec(),
+            verbose: false,
+        }
+    }
+}
+
+/// A grid that `solve` filled in completely, with every value obeying the grid's rules.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SolveOutcome {
+    /// The number of times the solver went through its strategies
+    pub steps: u32,
+}
+
+/// Works out the candidates of a freshly loaded grid and applies the configured strategies until the grid is solved.
+/// Fails when a cell runs out of candidates or the finished grid breaks a rule, or when the strategies all come up
+/// empty before the grid is solved.
+pub fn solve(grid: &mut Grid, config: &SolverConfig) -> Result<SolveOutcome, DokuError> {
+    grid.compute_candidates();
+    grid.check_consistency()?;
+    let mut steps: u32 = 0;
+    while !grid.is_solved() {
+        if config.verbose {
+            print_board(grid, steps);
+        }
+        steps += 1;
+        if config.verbose {
+            println!();
+        }
+        let progress = config.strategies.iter().any(|strategy| {
+            if config.verbose {
+                println!("Trying {}", strategy.name());
+            }
+            let found = strategy.apply(grid, config.verbose);
+            if !found && config.verbose {
+                println!("{}", strategy.nothing_found());
+            }
+            found
+        });
+        if !progress {
+            if config.verbose {
+                print_board(grid, steps);
+            }
+            return Err(DokuError::Unsolvable { steps });
+        }
+        grid.check_consistency()?;
+    }
+    if config.verbose {
+        println!("Solved:");
+        print_board(grid, steps);
+    }
+    grid.validate()?;
+    Ok(SolveOutcome { steps })
+}
+
+fn print_board(grid: &Grid, i: u32) {
+    println!("### ({i}) Current board state:");
+    output::output_grid(grid);
+    println!("### ({i}) Candidates:");
+    output::output_candidates(grid);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Coord;
+    use crate::puzzle::{load_puzzle, parse_puzzle};
+
+    #[test]
+    fn solves_with_all_strategies() {
+        let mut grid = parse_puzzle(include_str!("../puzzles/simple_link.txt")).unwrap();
+        assert!(solve(&mut grid, &SolverConfig::default()).is_ok());
+        assert!(grid.is_solved());
+    }
+
+    #[test]
+    fn solves_every_example_puzzle() {
+        let puzzles = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("puzzles");
+        let mut paths: Vec<_> = std::fs::read_dir(puzzles)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        paths.sort();
+        assert!(!paths.is_empty());
+        for path in paths {
+            let mut grid = load_puzzle(path.to_str().unwrap()).unwrap();
+            let outcome = solve(&mut grid, &SolverConfig::default());
+            assert!(outcome.is_ok(), "{}: {outcome:?}", path.display());
+            assert!(grid.is_solved(), "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn gets_stuck_without_the_strategies_it_needs() {
+        let mut grid = parse_puzzle(include_str!("../puzzles/simple_link.txt")).unwrap();
+        let config = SolverConfig {
+            strategies: vec![Strategy::Single],
+            ..SolverConfig::default()
+        };
+        assert!(matches!(
+            solve(&mut grid, &config),
+            Err(DokuError::Unsolvable { .. })
+        ));
+        assert!(!grid.is_solved());
+    }
+
+    #[test]
+    fn reports_cells_without_candidates() {
+        let mut grid = Grid::new(4).unwrap();
+        for (x, value) in [(0, 1), (1, 2), (2, 3)] {
+            grid.set_given_value(Coord::new(x, 0), value);
+        }
+        grid.set_given_value(Coord::new(3, 1), 4);
+        match solve(&mut grid, &SolverConfig::default()) {
+            Err(DokuError::Contradiction { coord, .. }) => assert_eq!(coord, Coord::new(3, 0)),
+            other => panic!("Expected a contradiction, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn naked_pairs_expose_a_third_cell_with_the_same_candidates() {
+        // Three cells of the top row can only hold a 1 or a 2, which can't all be true
+        let mut grid = parse_puzzle(
+            "size 4\n. . . .\n. . . .\n. . . .\n. . . .\n\
+             candidates 0,0 1 2\ncandidates 1,0 1 2\ncandidates 2,0 1 2\n",
+        )
+        .unwrap();
+        let config = SolverConfig {
+            strategies: vec![Strategy::NakedPair],
+            ..SolverConfig::default()
+        };
+        assert!(matches!(
+            solve(&mut grid, &config),
+            Err(DokuError::Contradiction { .. })
+        ));
+    }
+}
@@ -1,3 +1,5 @@
+pub mod innies_outies;
+pub mod killer_cage;
 pub mod naked_pair;
 pub mod region_intersection;
 pub mod simple_link;
@@ -8,27 +10,27 @@ use crate::data::{Grid, Reduction, Solution};
 pub trait SolveStrategy {
     fn solutions(grid: &Grid) -> Vec<Solution>;
 
-    fn try_solution(grid: &mut Grid) -> bool {
-        let solutions = Self::solutions(&grid);
-        if !solutions.is_empty() {
-            println!("### Found solutions:");
-            solutions.iter().for_each(|s| println!("{s}"));
-            grid.apply_solutions(&solutions);
-        }
-        !solutions.is_empty()
+    /// Applies the strategy's solutions, if it finds any, and returns them. They come in row-major order, so that the
+    /// log of a solve is the same every time.
+    fn try_solution(grid: &mut Grid) -> Vec<Solution> {
+        let mut solutions = Self::solutions(grid);
+        solutions.sort();
+        solutions.dedup();
+        grid.apply_solutions(&solutions);
+        solutions
     }
 }
 
 pub trait ReduceStrategy {
     fn reduce_candidates(grid: &Grid) -> Vec<Reduction>;
 
-    fn try_reduction(grid: &mut Grid) -> bool {
-        let reductions = Self::reduce_candidates(&grid);
-        if !reductions.is_empty() {
-            println!("### Found reductions:");
-            reductions.iter().for_each(|r| println!("{r}"));
-            grid.apply_reductions(&reductions);
-        }
-        !reductions.is_empty()
+    /// Applies the strategy's reductions, if it finds any, and returns them. They come in row-major order, and by
+    /// candidate within a cell.
+    fn try_reduction(grid: &mut Grid) -> Vec<Reduction> {
+        let mut reductions = Self::reduce_candidates(grid);
+        reductions.sort();
+        reductions.dedup();
+        grid.apply_reductions(&reductions);
+        reductions
     }
 }
@@ -0,0 +1,137 @@
+use std::collections::BTreeSet;
+
+use super::ReduceStrategy;
+use crate::constraints::cage::sum_candidates;
+use crate::data::{Coord, Grid, Reduction};
+
+/// The number of cells we're willing to consider as innies or outies. Bigger groups rarely lead to any reductions and
+/// are expensive to enumerate.
+const MAX_CELLS: usize = 5;
+
+/// Innies and outies are the Killer Sudoku "rule of 45": every region adds up to 1 + 2 + ... + size (45 in a 9x9 grid).
+/// If the cages that lie completely inside a region cover all but a few of its cells (the innies), those cells must add
+/// up to the region's sum minus the sum of those cages. Inversely, if the cages that overlap a region cover all of it
+/// and stick out by a few cells (the outies), those cells must add up to the sum of the cages minus the region's sum.
+/// Either way we end up with a group of cells with a known sum, which restricts their candidates like a cage would.
+pub struct InniesOuties {}
+
+impl ReduceStrategy for InniesOuties {
+    fn reduce_candidates(grid: &Grid) -> Vec<Reduction> {
+        let mut reductions: Vec<Reduction> = Vec::new();
+        if grid.cages().is_empty() {
+            return reductions;
+        }
+        let region_sum: u32 = grid.possible_values().map(|v| v as u32).sum();
+        for region in grid.regions().iter() {
+            let region_coords = region.cell_coords();
+
+            // Innies
+            let inside_cages: Vec<_> = grid
+                .cages()
+                .iter()
+                .filter(|c| c.sum().is_some() && c.is_within(region))
+                .collect();
+            let mut innies = region_coords.clone();
+            let mut inside_sum: u32 = 0;
+            for cage in inside_cages.iter() {
+                innies.retain(|coord| !cage.contains_coord(coord));
+                inside_sum += cage.sum().unwrap();
+            }
+            if !inside_cages.is_empty() && inside_sum <= region_sum {
+                reduce_group(grid, &innies, region_sum - inside_sum, &mut reductions);
+            }
+
+            // Outies
+            let overlapping_cages: Vec<_> = grid
+                .cages()
+                .iter()
+                .filter(|c| region_coords.iter().any(|coord| c.contains_coord(coord)))
+                .collect();
+            let mut covered: BTreeSet<Coord> = BTreeSet::new();
+            let mut overlapping_sum: u32 = 0;
+            let mut all_sums = true;
+            for cage in overlapping_cages.iter() {
+                covered.extend(cage.cell_coords().iter().copied());
+                match cage.sum() {
+                    Some(sum) => overlapping_sum += sum,
+                    None => all_sums = false,
+                }
+            }
+            if all_sums && region_coords.is_subset(&covered) && overlapping_sum >= region_sum {
+                let outies: BTreeSet<Coord> = covered.difference(region_coords).cloned().collect();
+                reduce_group(grid, &outies, overlapping_sum - region_sum, &mut reductions);
+            }
+        }
+        reductions
+    }
+}
+
+fn reduce_group(grid: &Grid, group: &BTreeSet<Coord>, sum: u32, reductions: &mut Vec<Reduction>) {
+    if group.is_empty() || group.len() > MAX_CELLS {
+        return;
+    }
+    if group.iter().all(|coord| !grid.cell(coord).is_empty()) {
+        return;
+    }
+    let coords: Vec<Coord> = group.iter().cloned().collect();
+    let possible_values = sum_candidates(grid, &coords, sum);
+    for (coord, values) in coords.iter().zip(possible_values.iter()) {
+        let cell = grid.cell(coord);
+        if !cell.is_empty() {
+            continue;
+        }
+        for candidate in cell.candidates.difference(values) {
+            reductions.push(Reduction::new(*coord, candidate));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::candidates::Candidates;
+
+    #[test]
+    fn innies_take_what_the_cages_leave_of_the_region() {
+        let mut grid = Grid::new(9).unwrap();
+        // The cages in the top row add up to 36, which leaves 9 for the last cell
+        for (x, sum) in [(0, 3), (2, 7), (4, 11), (6, 15)] {
+            grid.add_cage(
+                sum,
+                BTreeSet::from([Coord::new(x, 0), Coord::new(x + 1, 0)]),
+            );
+        }
+        grid.compute_candidates();
+        let reductions = InniesOuties::reduce_candidates(&grid);
+        assert!(reductions.iter().all(|r| r.coord() == Coord::new(8, 0)));
+        grid.apply_reductions(&reductions);
+        assert_eq!(
+            grid.cell(&Coord::new(8, 0)).candidates,
+            Candidates::from([9])
+        );
+    }
+
+    #[test]
+    fn outies_take_what_the_cages_add_to_the_region() {
+        let mut grid = Grid::new(9).unwrap();
+        // The cages cover the first column and one more cell, and add up to 5 more than the column
+        grid.add_cage(15, (0..5).map(|y| Coord::new(0, y)).collect());
+        let mut bottom: BTreeSet<Coord> = (5..9).map(|y| Coord::new(0, y)).collect();
+        bottom.insert(Coord::new(1, 8));
+        grid.add_cage(35, bottom);
+        grid.compute_candidates();
+        let reductions = InniesOuties::reduce_candidates(&grid);
+        grid.apply_reductions(&reductions);
+        assert_eq!(
+            grid.cell(&Coord::new(1, 8)).candidates,
+            Candidates::from([5])
+        );
+    }
+
+    #[test]
+    fn does_nothing_without_cages() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.compute_candidates();
+        assert!(InniesOuties::reduce_candidates(&grid).is_empty());
+    }
+}
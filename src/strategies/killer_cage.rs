@@ -0,0 +1,102 @@
+use super::ReduceStrategy;
+use crate::constraints::cage::operation_candidates;
+use crate::data::{Coord, Grid, Reduction};
+
+/// Killer cages restrict their cells to the combinations of values that add up to the cage's sum. For example, a cage of
+/// two cells that adds up to 3 can only contain a 1 and a 2, so every other candidate can be removed from those cells.
+/// KenKen cages work the same way for their own operation, e.g. a 2-cell cage with a quotient of 3 can only hold 1 & 3
+/// or 2 & 6.
+pub struct KillerCage {}
+
+impl ReduceStrategy for KillerCage {
+    fn reduce_candidates(grid: &Grid) -> Vec<Reduction> {
+        let mut reductions: Vec<Reduction> = Vec::new();
+        for cage in grid.cages().iter() {
+            let coords: Vec<Coord> = cage.cell_coords().iter().copied().collect();
+            let possible_values =
+                operation_candidates(grid, &coords, cage.operation(), cage.target());
+            for (coord, values) in coords.iter().zip(possible_values.iter()) {
+                let cell = grid.cell(coord);
+                if !cell.is_empty() {
+                    continue;
+                }
+                for candidate in cell.candidates.difference(values) {
+                    reductions.push(Reduction::new(*coord, candidate));
+                }
+            }
+        }
+        reductions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::candidates::Candidates;
+    use crate::constraints::cage::{sum_candidates, CageOperation};
+    use crate::data::RegionFamily;
+
+    #[test]
+    fn cage_of_two_summing_to_three_holds_one_and_two() {
+        let mut grid = Grid::new(9).unwrap();
+        let coords = vec![Coord::new(0, 0), Coord::new(1, 0)];
+        grid.add_cage(3, coords.iter().cloned().collect());
+        grid.compute_candidates();
+        let possible_values = sum_candidates(&grid, &coords, 3);
+        assert_eq!(possible_values[0], Candidates::from([1, 2]));
+        assert_eq!(possible_values[1], Candidates::from([1, 2]));
+        let reductions = KillerCage::reduce_candidates(&grid);
+        assert_eq!(reductions.len(), 14);
+    }
+
+    #[test]
+    fn cells_that_do_not_see_each_other_can_repeat() {
+        let mut grid = Grid::new(9).unwrap();
+        grid.compute_candidates();
+        let coords = vec![Coord::new(0, 0), Coord::new(4, 4)];
+        let possible_values = sum_candidates(&grid, &coords, 2);
+        assert_eq!(possible_values[0], Candidates::from([1]));
+        assert_eq!(possible_values[1], Candidates::from([1]));
+    }
+
+    #[test]
+    fn kenken_cages_allow_repeats_outside_rows_and_columns() {
+        let families = [RegionFamily::Rows, RegionFamily::Columns];
+        let mut grid = Grid::with_subgrids(6, &[Coord::new(0, 0)], &families).unwrap();
+        // An L-shaped cage whose corner shares a row with one cell and a column with the other
+        let coords = [Coord::new(0, 0), Coord::new(1, 0), Coord::new(0, 1)];
+        grid.add_arithmetic_cage(CageOperation::Product, 4, coords.iter().cloned().collect())
+            .unwrap();
+        let pair = [Coord::new(3, 3), Coord::new(4, 3)];
+        grid.add_arithmetic_cage(CageOperation::Quotient, 3, pair.iter().cloned().collect())
+            .unwrap();
+        grid.compute_candidates();
+        grid.apply_reductions(&KillerCage::reduce_candidates(&grid));
+        // 4 is either 4 × 1 × 1 or 1 × 2 × 2, with the repeated value in the two cells that don't share a row or column
+        assert_eq!(grid.cell(&coords[0]).candidates, Candidates::from([1, 4]));
+        assert_eq!(grid.cell(&coords[1]).candidates, Candidates::from([1, 2]));
+        assert_eq!(grid.cell(&coords[2]).candidates, Candidates::from([1, 2]));
+        assert_eq!(
+            grid.cell(&pair[0]).candidates,
+            Candidates::from([1, 2, 3, 6])
+        );
+    }
+
+    #[test]
+    fn products_too_big_for_a_u32_make_no_target() {
+        assert_eq!(CageOperation::Product.apply(&[16; 8]), None);
+        let families = [RegionFamily::Rows, RegionFamily::Columns];
+        let mut grid = Grid::with_subgrids(16, &[Coord::new(0, 0)], &families).unwrap();
+        // 2^31 takes eight powers of two of at least 8, while eight 16s overflow on the last multiplication
+        let coords: Vec<Coord> = (0..8).map(|i| Coord::new(i, i)).collect();
+        grid.add_arithmetic_cage(
+            CageOperation::Product,
+            1 << 31,
+            coords.iter().cloned().collect(),
+        )
+        .unwrap();
+        grid.compute_candidates();
+        grid.apply_reductions(&KillerCage::reduce_candidates(&grid));
+        assert_eq!(grid.cell(&coords[0]).candidates, Candidates::from([8, 16]));
+    }
+}
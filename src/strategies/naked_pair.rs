@@ -1,7 +1,7 @@
 // This strategy can be replaced down the line with a strategy that considers both naked and hidden pairs
 // And it can probably also be generalized into a strategy that considers tuples of any size, not just pairs...
 
-use std::collections::HashSet;
+use std::collections::BTreeSet;
 
 use super::ReduceStrategy;
 use crate::data::{Cell, Coord, Grid, Reduction};
@@ -12,13 +12,13 @@ impl ReduceStrategy for NakedPair {
     fn reduce_candidates(grid: &Grid) -> Vec<Reduction> {
         let mut reductions: Vec<Reduction> = Vec::new();
         // for each region in the grid
-        for region in grid.regions.iter() {
+        for region in grid.regions().iter() {
             // Check if there are two and only two cells that have the same two candidates (and no other candidates).
 
             // NOTE: This can probably be implemented more efficiently. We now have a bunch of nested loops over the
             //  cells of the region.
-            let cells = grid.cells_for_region(region);
-            let mut visited: HashSet<Coord> = HashSet::new();
+            let cells: Vec<&Cell> = grid.cells_for_region(region).collect();
+            let mut visited: BTreeSet<Coord> = BTreeSet::new();
             for cell in cells.iter() {
                 if !cell.is_empty() {
                     continue;
@@ -34,13 +34,9 @@ impl ReduceStrategy for NakedPair {
                     .filter(|c| c.coord != cell.coord && c.candidates == cell.candidates)
                     .cloned()
                     .collect();
-                if !shared_cells.is_empty() {
-                    if shared_cells.len() > 1 {
-                        panic!(
-                            "At most one cell sharing the same set of candidates should be found"
-                        );
-                    }
-                    let paired_cell = shared_cells.get(0).unwrap();
+                // More than one other cell with the same two candidates means the region can't be filled in. Treating
+                // the first one as the pair then empties out the others, so the solver notices the contradiction.
+                if let Some(paired_cell) = shared_cells.first() {
                     visited.insert(cell.coord);
                     visited.insert(paired_cell.coord);
                     for other_cell in cells
@@ -48,12 +44,9 @@ impl ReduceStrategy for NakedPair {
                         .filter(|c| c.coord != cell.coord && c.coord != paired_cell.coord)
                         .cloned()
                     {
-                        other_cell
-                            .candidates
-                            .intersection(&cell.candidates)
-                            .for_each(|candidate| {
-                                reductions.push(Reduction::new(other_cell.coord, *candidate))
-                            });
+                        for candidate in other_cell.candidates.intersection(&cell.candidates) {
+                            reductions.push(Reduction::new(other_cell.coord, candidate));
+                        }
                     }
                 }
             }
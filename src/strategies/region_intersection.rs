@@ -1,7 +1,5 @@
-use std::ptr;
-
 use super::ReduceStrategy;
-use crate::data::{Grid, Reduction, Region};
+use crate::data::{Grid, Reduction, RegionId};
 
 /// Region Intersection is a way to reduce the possible candidates in the Sudoku grid by looking at ways regions intersect.
 /// For example, if the first row of the grid only has 7 as a candidate in the first three cells, there is an intersection with
@@ -15,7 +13,7 @@ impl ReduceStrategy for RegionIntersection {
     fn reduce_candidates(grid: &Grid) -> Vec<Reduction> {
         let mut reductions: Vec<Reduction> = Vec::new();
         // for each region in the grid
-        for region in grid.regions.iter() {
+        for region in grid.regions().iter() {
             // for each possible value
             for candidate in grid.possible_values() {
                 // collect cells in the region that have the possible value as a candidate
@@ -24,20 +22,18 @@ impl ReduceStrategy for RegionIntersection {
                     continue;
                 }
                 // check intersection with other regions that hold all those cells
-                //   We filter out the region we're currently checking. But this pointer comparison is a bit meh. Either we should id the regions some other way, or maybe we can just not do any of this..?
-                //   We would just erase the candidate from any of the other cells in the region, but we already know they're not there anyway, because we gathered all the cells_with_candidate just now.
-                let intersecting_regions: Vec<&Region> = grid
+                //   The region we're currently checking holds them too, but we already know the candidate isn't in any
+                //   of its other cells, so we skip it.
+                let intersecting_regions: Vec<RegionId> = grid
                     .regions_for_coords(&cells_in_region_with_candidate)
-                    .iter()
-                    .filter(|&r| !ptr::eq(*r, region as *const Region))
-                    .map(|r| *r)
+                    .filter(|&id| id != region.id())
                     .collect();
                 // if such a region exists: Remove the candidate value from the cells in that region that do not intersect with the current region and that do have it as a candidate.
-                for intersecting_region in intersecting_regions.iter() {
-                    for cell in grid.cells_for_region(&intersecting_region) {
+                for intersecting_region in intersecting_regions {
+                    for cell in grid.cells_for_region(grid.region_by_id(intersecting_region)) {
                         if cell.is_empty()
                             && !cells_in_region_with_candidate.contains(&cell.coord)
-                            && cell.candidates.contains(&candidate)
+                            && cell.candidates.contains(candidate)
                         {
                             reductions.push(Reduction::new(cell.coord, candidate));
                         }
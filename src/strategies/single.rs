@@ -11,10 +11,10 @@ pub struct Single {}
 impl SolveStrategy for Single {
     fn solutions(grid: &Grid) -> Vec<Solution> {
         let mut found: Vec<Solution> = Vec::new();
-        for cell in grid.cells.iter() {
+        for cell in grid.cells().iter() {
             if cell.is_empty() && cell.candidates.len() == 1 {
-                let value = cell.candidates.iter().next().unwrap();
-                found.push(Solution::new(cell.coord, *value));
+                let value = cell.candidates.min().unwrap();
+                found.push(Solution::new(cell.coord, value));
             }
         }
         found